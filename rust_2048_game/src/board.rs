@@ -1,27 +1,142 @@
+use std::fmt;
+use std::str::FromStr;
+
 use rand::Rng;
 use rand::prelude::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::merge::MergeRule;
+use crate::spawner::SpawnRules;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Board {
     cells: [[u32; 4]; 4],
     pub score: u32,
+    // 旧存档没有这个字段，按经典规则处理
+    #[cfg_attr(feature = "serde", serde(default))]
+    rule: MergeRule,
+    // 有预告的变体中下一个新方块的值
+    #[cfg_attr(feature = "serde", serde(default))]
+    next: Option<u32>,
+    // 障碍格的位掩码，第 row * 4 + col 位表示该格是障碍；障碍格的数值始终为0
+    #[cfg_attr(feature = "serde", serde(default))]
+    blockers: u16,
 }
 
 impl Board {
+    #[cfg(test)]
     pub fn new() -> Self {
+        Self::new_with_rng(&mut rand::thread_rng())
+    }
+
+    // 使用指定的随机数生成器放置初始方块，便于按种子复现
+    pub fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new_with_rules(MergeRule::Classic, &SpawnRules::CLASSIC, rng)
+    }
+
+    pub fn new_with_rules<R: Rng + ?Sized>(rule: MergeRule, rules: &SpawnRules, rng: &mut R) -> Self {
         let mut board = Board {
             cells: [[0; 4]; 4],
             score: 0,
+            rule,
+            next: None,
+            blockers: 0,
         };
-        board.add_random_tile();
-        board.add_random_tile();
+        for _ in 0..rules.blockers {
+            board.add_blocker(rules, rng);
+        }
+        for _ in 0..rules.initial_tiles {
+            board.add_tile_with_rules(rules, rng);
+        }
         board
     }
 
+    // 从给定的格子构造棋盘，不会额外生成随机方块，分数为0
+    pub fn from_cells(cells: [[u32; 4]; 4]) -> Self {
+        Board {
+            cells,
+            score: 0,
+            rule: MergeRule::Classic,
+            next: None,
+            blockers: 0,
+        }
+    }
+
+    pub fn with_merge_rule(mut self, rule: MergeRule) -> Self {
+        self.rule = rule;
+        if !rule.has_preview() {
+            self.next = None;
+        }
+        self
+    }
+
+    pub fn next_tile(&self) -> Option<u32> {
+        self.next
+    }
+
+    pub fn merge_rule(&self) -> MergeRule {
+        self.rule
+    }
+
+    pub fn to_cells(&self) -> [[u32; 4]; 4] {
+        self.cells
+    }
+
     pub fn get_cell(&self, row: usize, col: usize) -> u32 {
         self.cells[row][col]
     }
 
+    // 障碍格不能移动也不能合并，滑动时相当于墙
+    pub fn is_blocker(&self, row: usize, col: usize) -> bool {
+        self.blockers & (1 << (row * 4 + col)) != 0
+    }
+
+    pub fn set_blocker(&mut self, row: usize, col: usize, blocker: bool) {
+        if blocker {
+            self.blockers |= 1 << (row * 4 + col);
+            self.cells[row][col] = 0;
+        } else {
+            self.blockers &= !(1 << (row * 4 + col));
+        }
+    }
+
+    pub fn blocker_count(&self) -> u32 {
+        self.blockers.count_ones()
+    }
+
+    // 在一个可以出块的空格上放置障碍
+    pub fn add_blocker<R: Rng + ?Sized>(&mut self, rules: &SpawnRules, rng: &mut R) {
+        if let Some(&(row, col)) = self.spawn_cells(rules).choose(rng) {
+            self.set_blocker(row, col, true);
+        }
+    }
+
+    pub fn max_tile(&self) -> u32 {
+        self.cells.iter().flatten().copied().max().unwrap_or(0)
+    }
+
+    // 紧凑格式：16个十六进制数字，每位是方块的指数（0表示空格）
+    // 超过32768的方块、非经典变体以及有障碍的棋盘无法表示，返回None
+    pub fn to_compact(&self) -> Option<String> {
+        if self.rule != MergeRule::Classic || self.blockers != 0 {
+            return None;
+        }
+        let mut compact = String::with_capacity(16);
+        for row in self.cells.iter() {
+            for &cell in row.iter() {
+                let exponent = self.rule.rank(cell);
+                if (cell != 0 && !cell.is_power_of_two()) || cell == 1 || exponent > 15 {
+                    return None;
+                }
+                compact.push(std::char::from_digit(exponent, 16)?);
+            }
+        }
+        Some(compact)
+    }
+
+    // 障碍格既不算空格，也不和相邻方块合并
     pub fn is_game_over(&self) -> bool {
         if self.has_empty_cells() {
             return false;
@@ -30,8 +145,8 @@ impl Board {
         for i in 0..4 {
             for j in 0..4 {
                 let current = self.cells[i][j];
-                if (i < 3 && current == self.cells[i + 1][j]) ||
-                   (j < 3 && current == self.cells[i][j + 1]) {
+                if (i < 3 && self.rule.merge(current, self.cells[i + 1][j]).is_some()) ||
+                   (j < 3 && self.rule.merge(current, self.cells[i][j + 1]).is_some()) {
                     return false;
                 }
             }
@@ -39,171 +154,166 @@ impl Board {
         true
     }
 
-    pub fn has_won(&self) -> bool {
-        for row in 0..4 {
-            for col in 0..4 {
-                if self.cells[row][col] == 2048 {
-                    return true;
-                }
-            }
-        }
-        false
+    // 是否已经出现不小于目标值的方块
+    pub fn has_reached(&self, target: u32) -> bool {
+        self.max_tile() >= target
     }
 
-    fn has_empty_cells(&self) -> bool {
-        self.cells.iter().any(|row| row.iter().any(|&cell| cell == 0))
+    // 棋盘上的方块数，不含空格和障碍
+    pub fn tile_count(&self) -> usize {
+        self.cells.iter().flatten().filter(|&&cell| cell != 0).count()
     }
 
-    fn add_random_tile(&mut self) {
-        if !self.has_empty_cells() {
-            return;
-        }
+    fn has_empty_cells(&self) -> bool {
+        !self.empty_cells().is_empty()
+    }
 
-        let mut rng = rand::thread_rng();
+    pub fn empty_cells(&self) -> Vec<(usize, usize)> {
         let mut empty_cells = Vec::new();
 
         for i in 0..4 {
             for j in 0..4 {
-                if self.cells[i][j] == 0 {
+                if self.cells[i][j] == 0 && !self.is_blocker(i, j) {
                     empty_cells.push((i, j));
                 }
             }
         }
+        empty_cells
+    }
 
-        if let Some(&(row, col)) = empty_cells.choose(&mut rng) {
-            self.cells[row][col] = if rng.gen_bool(0.9) { 2 } else { 4 };
+    pub fn set_cell(&mut self, row: usize, col: usize, value: u32) {
+        self.cells[row][col] = value;
+    }
+
+    // 按规则可以放置新方块的空格，有预告的变体总是只在边缘出块
+    pub fn spawn_cells(&self, rules: &SpawnRules) -> Vec<(usize, usize)> {
+        let mut cells = self.empty_cells();
+        if rules.edges_only || self.rule.has_preview() {
+            cells.retain(|&(row, col)| row == 0 || row == 3 || col == 0 || col == 3);
         }
+        cells
     }
 
-    pub fn move_tiles(&mut self, direction: Direction) -> bool {
-        let old_cells = self.cells;
-        let mut moved = false;
+    pub fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.add_tile_with_rules(&SpawnRules::CLASSIC, rng);
+    }
 
-        match direction {
-            Direction::Up => self.move_up(),
-            Direction::Down => self.move_down(),
-            Direction::Left => self.move_left(),
-            Direction::Right => self.move_right(),
+    // 放置一个新方块，没有可用空格时不做任何事
+    pub fn add_tile_with_rules<R: Rng + ?Sized>(&mut self, rules: &SpawnRules, rng: &mut R) {
+        if let Some(&(row, col)) = self.spawn_cells(rules).choose(rng) {
+            let value = match self.next {
+                Some(next) => next,
+                None => rules.pick_value(self.rule.spawn_values(), rng),
+            };
+            self.place_spawned(row, col, value, rules, rng);
         }
+    }
 
-        if old_cells != self.cells {
-            moved = true;
-            self.add_random_tile();
+    // 放置新方块，有预告的变体同时抽出下一个方块
+    pub fn place_spawned<R: Rng + ?Sized>(&mut self, row: usize, col: usize, value: u32, rules: &SpawnRules, rng: &mut R) {
+        self.cells[row][col] = value;
+        if self.rule.has_preview() {
+            self.next = Some(rules.pick_value(self.rule.spawn_values(), rng));
         }
+    }
 
-        moved
+    #[cfg(test)]
+    pub fn move_tiles(&mut self, direction: Direction) -> bool {
+        self.move_tiles_with_rng(direction, &mut rand::thread_rng())
     }
 
-    fn move_left(&mut self) {
-        for row in 0..4 {
-            let mut merged = [false; 4];
-            for col in 1..4 {
-                if self.cells[row][col] != 0 {
-                    let mut new_col = col;
-                    while new_col > 0 {
-                        if self.cells[row][new_col - 1] == 0 {
-                            self.cells[row][new_col - 1] = self.cells[row][new_col];
-                            self.cells[row][new_col] = 0;
-                            new_col -= 1;
-                        } else if !merged[new_col - 1] && 
-                                  self.cells[row][new_col - 1] == self.cells[row][new_col] {
-                            self.cells[row][new_col - 1] *= 2;
-                            self.score += self.cells[row][new_col - 1];
-                            self.cells[row][new_col] = 0;
-                            merged[new_col - 1] = true;
-                            break;
-                        } else {
-                            break;
-                        }
-                    }
-                }
-            }
+    #[cfg(test)]
+    pub fn move_tiles_with_rng<R: Rng + ?Sized>(&mut self, direction: Direction, rng: &mut R) -> bool {
+        let moved = self.slide(direction);
+        if moved {
+            self.add_random_tile(rng);
         }
+        moved
     }
 
-    fn move_right(&mut self) {
-        for row in 0..4 {
-            let mut merged = [false; 4];
-            for col in (0..3).rev() {
-                if self.cells[row][col] != 0 {
-                    let mut new_col = col;
-                    while new_col < 3 {
-                        if self.cells[row][new_col + 1] == 0 {
-                            self.cells[row][new_col + 1] = self.cells[row][new_col];
-                            self.cells[row][new_col] = 0;
-                            new_col += 1;
-                        } else if !merged[new_col + 1] && 
-                                  self.cells[row][new_col + 1] == self.cells[row][new_col] {
-                            self.cells[row][new_col + 1] *= 2;
-                            self.score += self.cells[row][new_col + 1];
-                            self.cells[row][new_col] = 0;
-                            merged[new_col + 1] = true;
-                            break;
-                        } else {
-                            break;
-                        }
-                    }
+    // 只滑动合并，不生成新方块；返回棋盘是否发生变化
+    pub fn slide(&mut self, direction: Direction) -> bool {
+        let old_cells = self.cells;
+
+        for line in 0..4 {
+            // 按移动方向从靠墙的一端开始取出一行或一列
+            let positions: [(usize, usize); 4] = std::array::from_fn(|k| match direction {
+                Direction::Up => (k, line),
+                Direction::Down => (3 - k, line),
+                Direction::Left => (line, k),
+                Direction::Right => (line, 3 - k),
+            });
+            // 障碍把一行分成几段，每段各自滑向靠墙或靠障碍的一端
+            let blockers = self.blockers;
+            for segment in positions.split(|&(row, col)| blockers & (1 << (row * 4 + col)) != 0) {
+                let mut tiles = [0; 4];
+                for (tile, &(row, col)) in tiles.iter_mut().zip(segment) {
+                    *tile = self.cells[row][col];
+                }
+                let (merged, gained) = if self.rule.one_step() {
+                    slide_line_one_step(tiles, self.rule)
+                } else {
+                    slide_line(tiles, self.rule)
+                };
+                for (&(row, col), value) in segment.iter().zip(merged) {
+                    self.cells[row][col] = value;
                 }
+                self.score += gained;
             }
         }
+
+        old_cells != self.cells
     }
+}
 
-    fn move_up(&mut self) {
-        for col in 0..4 {
-            let mut merged = [false; 4];
-            for row in 1..4 {
-                if self.cells[row][col] != 0 {
-                    let mut new_row = row;
-                    while new_row > 0 {
-                        if self.cells[new_row - 1][col] == 0 {
-                            self.cells[new_row - 1][col] = self.cells[new_row][col];
-                            self.cells[new_row][col] = 0;
-                            new_row -= 1;
-                        } else if !merged[new_row - 1] && 
-                                  self.cells[new_row - 1][col] == self.cells[new_row][col] {
-                            self.cells[new_row - 1][col] *= 2;
-                            self.score += self.cells[new_row - 1][col];
-                            self.cells[new_row][col] = 0;
-                            merged[new_row - 1] = true;
-                            break;
-                        } else {
-                            break;
-                        }
-                    }
-                }
+// 把一行方块推向下标0的一端，每个方块每步最多合并一次；返回新的一行和得分
+// 六边形棋盘的行长度不同，补0到同一长度后也用这个函数
+pub fn slide_line<const N: usize>(tiles: [u32; N], rule: MergeRule) -> ([u32; N], u32) {
+    let mut result = [0; N];
+    let mut len = 0;
+    let mut last_merged = false;
+    let mut gained = 0;
+
+    for tile in tiles.into_iter().filter(|&tile| tile != 0) {
+        if len > 0 && !last_merged {
+            if let Some(merged) = rule.merge(tile, result[len - 1]) {
+                result[len - 1] = merged;
+                gained += merged;
+                last_merged = true;
+                continue;
             }
         }
+        result[len] = tile;
+        len += 1;
+        last_merged = false;
     }
+    (result, gained)
+}
 
-    fn move_down(&mut self) {
-        for col in 0..4 {
-            let mut merged = [false; 4];
-            for row in (0..3).rev() {
-                if self.cells[row][col] != 0 {
-                    let mut new_row = row;
-                    while new_row < 3 {
-                        if self.cells[new_row + 1][col] == 0 {
-                            self.cells[new_row + 1][col] = self.cells[new_row][col];
-                            self.cells[new_row][col] = 0;
-                            new_row += 1;
-                        } else if !merged[new_row + 1] && 
-                                  self.cells[new_row + 1][col] == self.cells[new_row][col] {
-                            self.cells[new_row + 1][col] *= 2;
-                            self.score += self.cells[new_row + 1][col];
-                            self.cells[new_row][col] = 0;
-                            merged[new_row + 1] = true;
-                            break;
-                        } else {
-                            break;
-                        }
-                    }
-                }
-            }
+// Threes 式滑动：从靠墙一端找到第一个能移动的方块，它和它后面的方块都只移动一格，每行最多一次合并
+fn slide_line_one_step(tiles: [u32; 4], rule: MergeRule) -> ([u32; 4], u32) {
+    let mut result = tiles;
+    for i in 1..4 {
+        if result[i] == 0 {
+            continue;
+        }
+        let moved = if result[i - 1] == 0 {
+            Some((result[i], 0))
+        } else {
+            rule.merge(result[i], result[i - 1]).map(|merged| (merged, merged))
+        };
+        if let Some((value, gained)) = moved {
+            result[i - 1] = value;
+            result.copy_within(i + 1.., i);
+            result[3] = 0;
+            return (result, gained);
         }
     }
+    (result, 0)
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Direction {
     Up,
     Down,
@@ -211,6 +321,134 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    // 在解答和说明文字里显示的箭头
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '↑',
+            Direction::Down => '↓',
+            Direction::Left => '←',
+            Direction::Right => '→',
+        }
+    }
+}
+
+// 文本格式：4行，每行4个数字，空格用"."表示，障碍用"#"表示
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.cells.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, &cell) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                if self.is_blocker(i, j) {
+                    write!(f, "{:>5}", "#")?;
+                } else if cell == 0 {
+                    write!(f, "{:>5}", ".")?;
+                } else {
+                    write!(f, "{:>5}", cell)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseBoardError {
+    RowCount(usize),
+    ColumnCount { row: usize, count: usize },
+    InvalidTile(String),
+    InvalidCompact(String),
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::RowCount(count) => write!(f, "棋盘需要4行，实际为{}行", count),
+            ParseBoardError::ColumnCount { row, count } => {
+                write!(f, "第{}行需要4个格子，实际为{}个", row + 1, count)
+            }
+            ParseBoardError::InvalidTile(tile) => write!(f, "非法方块: {}", tile),
+            ParseBoardError::InvalidCompact(text) => write!(f, "非法紧凑格式: {}", text),
+        }
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+// 接受Display输出的网格，或者16位十六进制指数的紧凑格式，按经典规则解析
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::parse_with_rule(s, MergeRule::Classic)
+    }
+}
+
+impl Board {
+    // 紧凑格式只用于经典规则，其它变体只接受网格
+    pub fn parse_with_rule(s: &str, rule: MergeRule) -> Result<Self, ParseBoardError> {
+        let text = s.trim();
+        if rule == MergeRule::Classic && text.len() == 16 && !text.contains(char::is_whitespace) {
+            return parse_compact(text);
+        }
+
+        let rows: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        if rows.len() != 4 {
+            return Err(ParseBoardError::RowCount(rows.len()));
+        }
+
+        let mut cells = [[0; 4]; 4];
+        let mut blockers = Vec::new();
+        for (i, line) in rows.iter().enumerate() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() != 4 {
+                return Err(ParseBoardError::ColumnCount { row: i, count: tokens.len() });
+            }
+            for (j, token) in tokens.iter().enumerate() {
+                if *token == "#" {
+                    blockers.push((i, j));
+                } else {
+                    cells[i][j] = parse_tile(token, rule)?;
+                }
+            }
+        }
+        let mut board = Board::from_cells(cells).with_merge_rule(rule);
+        for (row, col) in blockers {
+            board.set_blocker(row, col, true);
+        }
+        Ok(board)
+    }
+}
+
+fn parse_tile(token: &str, rule: MergeRule) -> Result<u32, ParseBoardError> {
+    if token == "." {
+        return Ok(0);
+    }
+    match token.parse::<u32>() {
+        Ok(0) => Ok(0),
+        Ok(value) if rule.is_valid_tile(value) => Ok(value),
+        _ => Err(ParseBoardError::InvalidTile(token.to_string())),
+    }
+}
+
+fn parse_compact(text: &str) -> Result<Board, ParseBoardError> {
+    let mut cells = [[0; 4]; 4];
+    for (index, ch) in text.chars().enumerate() {
+        let exponent = ch
+            .to_digit(16)
+            .ok_or_else(|| ParseBoardError::InvalidCompact(text.to_string()))?;
+        cells[index / 4][index % 4] = if exponent == 0 { 0 } else { 1 << exponent };
+    }
+    Ok(Board::from_cells(cells))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,42 +471,177 @@ mod tests {
 
     #[test]
     fn test_move_tiles() {
-        let mut board = Board::new();
-        board.cells = [
+        let mut board = Board::from_cells([
             [2, 2, 0, 0],
             [0, 0, 0, 0],
             [0, 0, 0, 0],
             [0, 0, 0, 0],
-        ];
+        ]);
 
         board.move_tiles(Direction::Left);
-        assert_eq!(board.cells[0][0], 4);
+        assert_eq!(board.get_cell(0, 0), 4);
         assert_eq!(board.score, 4);
     }
 
     #[test]
     fn test_game_over() {
-        let mut board = Board::new();
-        board.cells = [
+        let board = Board::from_cells([
             [2, 4, 2, 4],
             [4, 2, 4, 2],
             [2, 4, 2, 4],
             [4, 2, 4, 2],
-        ];
+        ]);
 
         assert!(board.is_game_over());
     }
 
     #[test]
     fn test_win_condition() {
-        let mut board = Board::new();
-        board.cells = [
+        let board = Board::from_cells([
             [2048, 0, 0, 0],
             [0, 0, 0, 0],
             [0, 0, 0, 0],
             [0, 0, 0, 0],
-        ];
+        ]);
+
+        assert!(board.has_reached(2048));
+        assert!(board.has_reached(512));
+        assert!(!board.has_reached(4096));
+    }
+
+    #[test]
+    fn test_seeded_board_is_reproducible() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let mut a = Board::new_with_rng(&mut StdRng::seed_from_u64(7));
+        let mut b = Board::new_with_rng(&mut StdRng::seed_from_u64(7));
+        assert_eq!(a, b);
+
+        let mut rng_a = StdRng::seed_from_u64(8);
+        let mut rng_b = StdRng::seed_from_u64(8);
+        for direction in Direction::ALL {
+            a.move_tiles_with_rng(direction, &mut rng_a);
+            b.move_tiles_with_rng(direction, &mut rng_b);
+        }
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let board = Board::from_cells([
+            [2, 4, 0, 0],
+            [0, 2048, 0, 8],
+            [0, 0, 0, 0],
+            [16, 0, 0, 32768],
+        ]);
+
+        let text = board.to_string();
+        assert_eq!(text.lines().next(), Some("    2     4     .     ."));
+        assert_eq!(text.parse::<Board>(), Ok(board.clone()));
+
+        let compact = board.to_compact().unwrap();
+        assert_eq!(compact, "12000b030000400f");
+        assert_eq!(compact.parse::<Board>(), Ok(board));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("2 2 2 2".parse::<Board>(), Err(ParseBoardError::RowCount(1)));
+        assert_eq!(
+            "2 . . .\n. . .\n. . . .\n. . . .".parse::<Board>(),
+            Err(ParseBoardError::ColumnCount { row: 1, count: 3 })
+        );
+        assert_eq!(
+            "3 . . .\n. . . .\n. . . .\n. . . .".parse::<Board>(),
+            Err(ParseBoardError::InvalidTile("3".to_string()))
+        );
+        assert!("12000b03000040fz".parse::<Board>().is_err());
+    }
+
+    #[test]
+    fn test_fibonacci_board() {
+        let mut board = Board::from_cells([
+            [1, 2, 3, 0],
+            [1, 1, 0, 0],
+            [5, 8, 13, 21],
+            [2, 2, 0, 0],
+        ])
+        .with_merge_rule(MergeRule::Fibonacci);
 
-        assert!(board.has_won());
+        assert!(board.slide(Direction::Left));
+        assert_eq!(board.get_cell(0, 0), 3);
+        assert_eq!(board.get_cell(0, 1), 3);
+        assert_eq!(board.get_cell(1, 0), 2);
+        // 5+8=13 之后不会再和同一步合并出的13合并
+        assert_eq!((board.get_cell(2, 0), board.get_cell(2, 1), board.get_cell(2, 2)), (13, 34, 0));
+        assert_eq!((board.get_cell(3, 0), board.get_cell(3, 1)), (2, 2));
+        assert_eq!(board.score, 3 + 2 + 13 + 34);
+
+        let stuck = Board::from_cells([
+            [2, 2, 2, 2],
+            [8, 8, 8, 8],
+            [2, 2, 2, 2],
+            [8, 8, 8, 8],
+        ]);
+        assert!(!stuck.is_game_over());
+        assert!(stuck.with_merge_rule(MergeRule::Fibonacci).is_game_over());
+
+        let parsed = Board::parse_with_rule("1 2 3 .\n. . . .\n. . . .\n. . . 5", MergeRule::Fibonacci).unwrap();
+        assert_eq!(parsed.merge_rule(), MergeRule::Fibonacci);
+        assert_eq!(parsed.to_compact(), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_threes_one_step_slide() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let mut board = Board::from_cells([
+            [0, 0, 3, 6],
+            [1, 2, 3, 3],
+            [3, 6, 0, 1],
+            [2, 2, 2, 2],
+        ])
+        .with_merge_rule(MergeRule::Threes);
+
+        assert!(board.slide(Direction::Left));
+        assert_eq!(board.to_cells(), [
+            [0, 3, 6, 0],
+            [3, 3, 3, 0],
+            [3, 6, 1, 0],
+            [2, 2, 2, 2],
+        ]);
+        assert_eq!(board.score, 3);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let rules = SpawnRules::CLASSIC;
+        let mut board = Board::new_with_rules(MergeRule::Threes, &rules, &mut rng);
+        let next = board.next_tile().unwrap();
+        board.slide(Direction::Left);
+        let before = board.empty_cells();
+        board.add_tile_with_rules(&rules, &mut rng);
+        let (row, col) = before.into_iter().find(|&(row, col)| board.get_cell(row, col) != 0).unwrap();
+        assert_eq!(board.get_cell(row, col), next);
+        assert!(row == 0 || row == 3 || col == 0 || col == 3);
+    }
+
+    #[test]
+    fn test_blockers_act_as_walls() {
+        let mut board: Board = "2 # 2 .\n2 2 # 4\n. . . .\n4 # 2 2".parse().unwrap();
+        assert_eq!(board.blocker_count(), 3);
+        assert_eq!(board.empty_cells().len(), 5);
+        assert!(board.to_string().starts_with("    2     #     2     ."));
+
+        assert!(board.slide(Direction::Left));
+        assert_eq!(board.to_string(), "2 # 2 .\n4 . # 4\n. . . .\n4 # 4 .".parse::<Board>().unwrap().to_string());
+        assert_eq!(board.score, 8);
+        assert_eq!(board.blocker_count(), 3);
+
+        // 障碍格不算空格，被障碍隔开的相同方块也不能合并
+        let stuck: Board = "2 4 2 4\n4 # 4 2\n2 4 # 4\n4 2 4 2".parse().unwrap();
+        assert!(stuck.is_game_over());
+        let open: Board = "2 4 2 4\n4 # 4 2\n2 4 . 4\n4 2 4 2".parse().unwrap();
+        assert!(!open.is_game_over());
+    }
+}
@@ -0,0 +1,141 @@
+use eframe::egui;
+use egui::{Color32, FontId, Pos2, Rect, Vec2};
+
+use crate::board::Board;
+use crate::merge::MergeRule;
+
+pub const BOARD_SIZE: f32 = 300.0;
+
+// 棋盘在当前位置下方居中
+pub fn board_rect(ui: &egui::Ui) -> Rect {
+    Rect::from_min_size(
+        Pos2::new(
+            (ui.available_width() - BOARD_SIZE) / 2.0,
+            ui.cursor().min.y + 20.0,
+        ),
+        Vec2::new(BOARD_SIZE, BOARD_SIZE),
+    )
+}
+
+pub fn cell_rect(board_rect: Rect, row: usize, col: usize) -> Rect {
+    let cell_size = board_rect.width() / 4.0;
+    let margin = cell_size / 15.0;
+    Rect::from_min_size(
+        Pos2::new(
+            board_rect.min.x + col as f32 * cell_size + margin,
+            board_rect.min.y + row as f32 * cell_size + margin,
+        ),
+        Vec2::splat(cell_size - 2.0 * margin),
+    )
+}
+
+pub fn tile_color(rule: MergeRule, value: u32) -> Color32 {
+    match rule {
+        MergeRule::Fibonacci => return fibonacci_color(rule.rank(value)),
+        MergeRule::Threes => return threes_color(value),
+        MergeRule::Classic => {}
+    }
+    match value {
+        0 => Color32::from_rgb(205, 193, 180),
+        2 => Color32::from_rgb(238, 228, 218),
+        4 => Color32::from_rgb(237, 224, 200),
+        8 => Color32::from_rgb(242, 177, 121),
+        16 => Color32::from_rgb(245, 149, 99),
+        32 => Color32::from_rgb(246, 124, 95),
+        64 => Color32::from_rgb(246, 94, 59),
+        128 => Color32::from_rgb(237, 207, 114),
+        256 => Color32::from_rgb(237, 204, 97),
+        512 => Color32::from_rgb(237, 200, 80),
+        1024 => Color32::from_rgb(237, 197, 63),
+        2048 => Color32::from_rgb(237, 194, 46),
+        _ => Color32::from_rgb(205, 193, 180),
+    }
+}
+
+// 斐波那契变体使用从浅绿到深青的渐变，与经典配色区分开
+fn fibonacci_color(rank: u32) -> Color32 {
+    if rank == 0 {
+        return Color32::from_rgb(205, 193, 180);
+    }
+    let t = ((rank - 1) as f32 / 16.0).min(1.0);
+    let lerp = |from: f32, to: f32| (from + (to - from) * t) as u8;
+    Color32::from_rgb(lerp(225.0, 20.0), lerp(242.0, 110.0), lerp(215.0, 120.0))
+}
+
+// Threes 变体沿用原作配色：1为蓝色，2为红色，3以上为白色
+fn threes_color(value: u32) -> Color32 {
+    match value {
+        0 => Color32::from_rgb(205, 193, 180),
+        1 => Color32::from_rgb(102, 204, 255),
+        2 => Color32::from_rgb(255, 102, 128),
+        _ => Color32::from_rgb(254, 254, 254),
+    }
+}
+
+pub fn text_color(rule: MergeRule, value: u32) -> Color32 {
+    let dark = match rule {
+        MergeRule::Threes => value >= 3,
+        _ => rule.rank(value) <= 2,
+    };
+    if dark {
+        Color32::from_rgb(119, 110, 101)
+    } else {
+        Color32::WHITE
+    }
+}
+
+// 在当前位置绘制一个小方块，预告下一个出现的数值
+pub fn draw_next_tile(ui: &mut egui::Ui, rule: MergeRule, value: u32) {
+    ui.horizontal(|ui| {
+        ui.label("下一个:");
+        let (rect, _) = ui.allocate_exact_size(Vec2::splat(28.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 4.0, tile_color(rule, value));
+        ui.painter().text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            value.to_string(),
+            FontId::proportional(16.0),
+            text_color(rule, value),
+        );
+    });
+}
+
+// 按 rect 的大小缩放绘制整个棋盘
+pub fn draw_board(painter: &egui::Painter, rect: Rect, board: &Board) {
+    let scale = rect.width() / BOARD_SIZE;
+
+    // 绘制背景
+    painter.rect_filled(rect, 5.0 * scale, Color32::from_rgb(187, 173, 160));
+
+    // 绘制每个格子
+    for row in 0..4 {
+        for col in 0..4 {
+            let cell_value = board.get_cell(row, col);
+            let cell_rect = cell_rect(rect, row, col);
+
+            // 障碍格画成深色并加上交叉线，和空格、方块区分开
+            if board.is_blocker(row, col) {
+                painter.rect_filled(cell_rect, 5.0 * scale, Color32::from_rgb(92, 84, 78));
+                let inner = cell_rect.shrink(cell_rect.width() * 0.25);
+                let stroke = egui::Stroke::new(4.0 * scale, Color32::from_rgb(140, 130, 122));
+                painter.line_segment([inner.left_top(), inner.right_bottom()], stroke);
+                painter.line_segment([inner.right_top(), inner.left_bottom()], stroke);
+                continue;
+            }
+
+            painter.rect_filled(cell_rect, 5.0 * scale, tile_color(board.merge_rule(), cell_value));
+
+            // 绘制数字
+            if cell_value > 0 {
+                let font_size = if cell_value >= 1000 { 24.0 } else { 32.0 };
+                painter.text(
+                    cell_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    cell_value.to_string(),
+                    FontId::proportional(font_size * scale),
+                    text_color(board.merge_rule(), cell_value),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use crate::board::{slide_line, Board};
+use crate::merge::MergeRule;
+use crate::spawner::SpawnRules;
+
+// 立方体棋盘的边长，共4层，每层4x4
+pub const SIZE: usize = 4;
+
+// 沿三条轴的六个移动方向：层内的上下左右，以及在层之间移动
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+    // 移向第1层
+    Front,
+    // 移向第4层
+    Back,
+}
+
+impl CubeDirection {
+    pub const ALL: [CubeDirection; 6] = [
+        CubeDirection::Up,
+        CubeDirection::Down,
+        CubeDirection::Left,
+        CubeDirection::Right,
+        CubeDirection::Front,
+        CubeDirection::Back,
+    ];
+}
+
+// 格子坐标为 (层, 行, 列)，合并规则与经典2048相同
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeBoard {
+    cells: [[[u32; SIZE]; SIZE]; SIZE],
+    pub score: u32,
+}
+
+impl CubeBoard {
+    pub fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut board = Self::from_cells([[[0; SIZE]; SIZE]; SIZE]);
+        for _ in 0..SpawnRules::CLASSIC.initial_tiles {
+            board.add_random_tile(rng);
+        }
+        board
+    }
+
+    pub fn from_cells(cells: [[[u32; SIZE]; SIZE]; SIZE]) -> Self {
+        CubeBoard { cells, score: 0 }
+    }
+
+    #[cfg(test)]
+    pub fn get_cell(&self, layer: usize, row: usize, col: usize) -> u32 {
+        self.cells[layer][row][col]
+    }
+
+    // 把一层当作普通棋盘，用于复用方形棋盘的绘制
+    pub fn layer(&self, layer: usize) -> Board {
+        Board::from_cells(self.cells[layer])
+    }
+
+    pub fn max_tile(&self) -> u32 {
+        self.cells.iter().flatten().flatten().copied().max().unwrap_or(0)
+    }
+
+    pub fn empty_cells(&self) -> Vec<(usize, usize, usize)> {
+        let mut empty_cells = Vec::new();
+        for layer in 0..SIZE {
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    if self.cells[layer][row][col] == 0 {
+                        empty_cells.push((layer, row, col));
+                    }
+                }
+            }
+        }
+        empty_cells
+    }
+
+    pub fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        if let Some(&(layer, row, col)) = self.empty_cells().choose(rng) {
+            self.cells[layer][row][col] = SpawnRules::CLASSIC.pick_value(MergeRule::Classic.spawn_values(), rng);
+        }
+    }
+
+    // 只滑动合并，不生成新方块；返回棋盘是否发生变化
+    pub fn slide(&mut self, direction: CubeDirection) -> bool {
+        let old_cells = self.cells;
+
+        for a in 0..SIZE {
+            for b in 0..SIZE {
+                // 按移动方向从靠墙的一端开始取出一条线
+                let positions: [(usize, usize, usize); SIZE] = std::array::from_fn(|k| match direction {
+                    CubeDirection::Up => (a, k, b),
+                    CubeDirection::Down => (a, SIZE - 1 - k, b),
+                    CubeDirection::Left => (a, b, k),
+                    CubeDirection::Right => (a, b, SIZE - 1 - k),
+                    CubeDirection::Front => (k, a, b),
+                    CubeDirection::Back => (SIZE - 1 - k, a, b),
+                });
+                let tiles = positions.map(|(layer, row, col)| self.cells[layer][row][col]);
+                let (merged, gained) = slide_line(tiles, MergeRule::Classic);
+                for ((layer, row, col), value) in positions.into_iter().zip(merged) {
+                    self.cells[layer][row][col] = value;
+                }
+                self.score += gained;
+            }
+        }
+
+        old_cells != self.cells
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        if !self.empty_cells().is_empty() {
+            return false;
+        }
+        for layer in 0..SIZE {
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    let current = self.cells[layer][row][col];
+                    if (layer + 1 < SIZE && self.cells[layer + 1][row][col] == current)
+                        || (row + 1 < SIZE && self.cells[layer][row + 1][col] == current)
+                        || (col + 1 < SIZE && self.cells[layer][row][col + 1] == current)
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cube_slide_between_layers() {
+        let mut cells = [[[0; SIZE]; SIZE]; SIZE];
+        cells[0][1][2] = 2;
+        cells[2][1][2] = 2;
+        cells[3][1][2] = 4;
+        let mut board = CubeBoard::from_cells(cells);
+
+        assert!(board.slide(CubeDirection::Back));
+        assert_eq!(board.get_cell(3, 1, 2), 4);
+        assert_eq!(board.get_cell(2, 1, 2), 4);
+        assert_eq!(board.get_cell(0, 1, 2), 0);
+        assert_eq!(board.score, 4);
+
+        assert!(board.slide(CubeDirection::Front));
+        assert_eq!(board.get_cell(0, 1, 2), 8);
+        assert_eq!(board.score, 12);
+        assert_eq!(board.layer(0).get_cell(1, 2), 8);
+    }
+
+    #[test]
+    fn test_cube_game_over() {
+        // 按三个坐标之和的奇偶交替放置2和4，任何相邻格都不相等
+        let cells = std::array::from_fn(|layer| {
+            std::array::from_fn(|row| std::array::from_fn(|col| if (layer + row + col) % 2 == 0 { 2 } else { 4 }))
+        });
+        let board = CubeBoard::from_cells(cells);
+        assert!(board.is_game_over());
+        for direction in CubeDirection::ALL {
+            assert!(!board.clone().slide(direction));
+        }
+    }
+}
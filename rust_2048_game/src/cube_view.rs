@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use eframe::egui;
+use egui::{Color32, Pos2, Rect, Vec2};
+
+use crate::board_view::{self, BOARD_SIZE};
+use crate::cube::{CubeBoard, CubeDirection, SIZE};
+use crate::game::SeededGame;
+use crate::highscore_view::ShapeRecords;
+use crate::highscores::{GameRecord, PLAYER_HUMAN};
+use crate::merge::MergeRule;
+use crate::rules::Challenge;
+use crate::strategy::StrategyKind;
+use crate::strategy_view;
+use crate::worker::MoveWorker;
+
+const CUBE_HIGHSCORE_FILE: &str = "cube_highscores.json";
+// 自动模式下两步之间的间隔（秒）
+const AUTO_INTERVAL: f64 = 0.1;
+
+// 方向键在层内移动，Q/E 在层之间移动
+const KEYS: [(egui::Key, CubeDirection); 6] = [
+    (egui::Key::ArrowUp, CubeDirection::Up),
+    (egui::Key::ArrowDown, CubeDirection::Down),
+    (egui::Key::ArrowLeft, CubeDirection::Left),
+    (egui::Key::ArrowRight, CubeDirection::Right),
+    (egui::Key::Q, CubeDirection::Front),
+    (egui::Key::E, CubeDirection::Back),
+];
+
+// 立方体模式页面：四层并排显示，可以手动或由自动玩家通过 Game 接口游玩
+pub struct CubeView {
+    game: SeededGame<CubeBoard>,
+    game_start_time: f64,
+    records: ShapeRecords,
+    auto: bool,
+    strategy_kind: StrategyKind,
+    worker: MoveWorker<CubeBoard>,
+    strategy_error: Option<String>,
+    last_auto_move: f64,
+}
+
+impl CubeView {
+    pub fn new() -> Self {
+        let strategy_kind = StrategyKind::Random;
+        Self {
+            game: SeededGame::new(rand::random()),
+            game_start_time: 0.0,
+            records: ShapeRecords::load(CUBE_HIGHSCORE_FILE),
+            auto: false,
+            worker: MoveWorker::new(strategy_kind.build(rand::random()).unwrap()),
+            strategy_kind,
+            strategy_error: None,
+            last_auto_move: 0.0,
+        }
+    }
+
+    fn player_name(&self) -> String {
+        if self.auto {
+            self.worker.name().to_string()
+        } else {
+            PLAYER_HUMAN.to_string()
+        }
+    }
+
+    // 手动模式处理按键，自动模式按间隔走一步并在结束后开始新的一局；返回是否需要继续重绘
+    pub fn update(&mut self, ctx: &egui::Context, now: f64) -> bool {
+        if !self.auto {
+            if !self.game.is_game_over() {
+                let pressed = ctx.input(|i| {
+                    KEYS.into_iter()
+                        .find(|(key, _)| i.key_pressed(*key))
+                        .map(|(_, direction)| direction)
+                });
+                if let Some(direction) = pressed {
+                    self.game.apply(direction);
+                }
+            }
+            return false;
+        }
+
+        // 策略在后台线程中计算，还没算完时下一帧继续等待
+        if now - self.last_auto_move >= AUTO_INTERVAL {
+            if self.game.is_game_over() {
+                self.last_auto_move = now;
+                self.reset_game(now);
+            } else if let Some(direction) = self.worker.next_move(self.game.board(), Duration::ZERO) {
+                self.last_auto_move = now;
+                if let Some(direction) = direction {
+                    self.game.apply(direction);
+                }
+            }
+        }
+        true
+    }
+
+    fn reset_game(&mut self, now: f64) {
+        let board = self.game.board();
+        self.records.add(GameRecord {
+            score: board.score,
+            max_tile: board.max_tile(),
+            moves: self.game.moves(),
+            duration_secs: now - self.game_start_time,
+            player: self.player_name(),
+            seed: self.game.seed(),
+            date: chrono::Local::now(),
+            merge: MergeRule::Classic,
+            challenge: Challenge::Endless,
+            target: MergeRule::Classic.default_target(),
+        });
+        self.game = SeededGame::new(rand::random());
+        self.game_start_time = now;
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, now: f64) {
+        ui.vertical_centered(|ui| {
+            ui.heading(format!("当前分数: {}", self.game.score()));
+            ui.label(format!("步数: {}", self.game.moves()));
+            self.records.draw_summary(ui);
+
+            ui.checkbox(&mut self.auto, "自动");
+            if self.auto {
+                // n-tuple 策略只支持方形棋盘，选择失败时继续使用原来的策略
+                if strategy_view::draw(ui, &mut self.strategy_kind) {
+                    match self.strategy_kind.build(rand::random()) {
+                        Ok(strategy) => {
+                            self.worker.set_strategy(strategy);
+                            self.strategy_error = None;
+                        }
+                        Err(e) => self.strategy_error = Some(e),
+                    }
+                }
+                if let Some(e) = &self.strategy_error {
+                    ui.colored_label(Color32::RED, e);
+                }
+            } else {
+                ui.label("方向键: 层内移动  Q/E: 移向第1层/第4层");
+            }
+
+            if self.game.is_game_over() {
+                ui.colored_label(Color32::RED, "游戏结束");
+            }
+            if ui.button("新游戏").clicked() {
+                self.reset_game(now);
+            }
+        });
+
+        // 四层从左到右排成一行，每层复用方形棋盘的绘制
+        let gap = 8.0;
+        let width = ((ui.available_width() - gap * (SIZE as f32 + 1.0)) / SIZE as f32).min(BOARD_SIZE);
+        let top = ui.cursor().min.y + 10.0;
+        for layer in 0..SIZE {
+            let rect = Rect::from_min_size(
+                Pos2::new(ui.max_rect().min.x + gap + layer as f32 * (width + gap), top + 20.0),
+                Vec2::splat(width),
+            );
+            ui.painter().text(
+                Pos2::new(rect.center().x, top + 8.0),
+                egui::Align2::CENTER_CENTER,
+                format!("第{}层", layer + 1),
+                egui::FontId::proportional(14.0),
+                ui.visuals().text_color(),
+            );
+            board_view::draw_board(ui.painter(), rect, &self.game.board().layer(layer));
+        }
+        ui.allocate_space(Vec2::new(ui.available_width(), width + 40.0));
+
+        ui.vertical_centered(|ui| {
+            self.records.draw_table(ui, "立方体最高分");
+        });
+    }
+}
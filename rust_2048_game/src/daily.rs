@@ -0,0 +1,302 @@
+use chrono::{Datelike, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::board::Direction;
+use crate::headless::parse_value;
+use crate::rules::GameStatus;
+use crate::session::GameSession;
+
+#[cfg(feature = "serde")]
+pub const DAILY_FILE: &str = "daily.json";
+
+// 每日挑战：种子只由日期决定，同一天所有人得到相同的开局和出块序列
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    // splitmix64，不依赖标准库哈希的实现，换版本编译结果也不变
+    let mut z = (date.num_days_from_ce() as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// 每日挑战固定使用经典规则和随机出块，不受界面设置影响
+pub fn daily_session(date: NaiveDate) -> GameSession {
+    GameSession::new(daily_seed(date))
+}
+
+// 当前对局是哪一天的每日挑战；每天只有第一次开始的那局是正式成绩，之后都是练习
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DailyRun {
+    pub date: NaiveDate,
+    pub official: bool,
+}
+
+// 一天的正式成绩
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DailyResult {
+    pub date: NaiveDate,
+    pub score: u32,
+    pub max_tile: u32,
+    pub moves: usize,
+    // 对局未结束就退出时保存的是当时的进度
+    pub finished: bool,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DailyHistory {
+    results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    pub fn get(&self, date: NaiveDate) -> Option<&DailyResult> {
+        self.results.iter().find(|result| result.date == date)
+    }
+
+    // 最近的日期在前
+    pub fn results(&self) -> impl Iterator<Item = &DailyResult> {
+        self.results.iter().rev()
+    }
+
+    // 开始当天的挑战；已经开始过则返回 None，这一局只能作为练习
+    pub fn start(&mut self, date: NaiveDate) -> Option<DailyRun> {
+        if self.get(date).is_some() {
+            return None;
+        }
+        let session = daily_session(date);
+        self.results.push(DailyResult {
+            date,
+            score: 0,
+            max_tile: session.board().max_tile(),
+            moves: 0,
+            finished: false,
+            code: share_code(date, &session),
+        });
+        Some(DailyRun { date, official: true })
+    }
+
+    // 更新正式对局的成绩，练习局不记录
+    pub fn record(&mut self, run: DailyRun, session: &GameSession) {
+        if !run.official {
+            return;
+        }
+        if let Some(result) = self.results.iter_mut().find(|result| result.date == run.date) {
+            result.score = session.score();
+            result.max_tile = session.board().max_tile();
+            result.moves = session.moves();
+            result.finished = session.is_game_over();
+            result.code = share_code(run.date, session);
+        }
+    }
+
+    // 读取存档时检查其中的每日挑战：当天成绩已经结束或存档比记录更早时只能作为练习
+    #[cfg(feature = "serde")]
+    pub fn resume(&self, run: DailyRun, session: &GameSession) -> DailyRun {
+        let official = run.official
+            && self
+                .get(run.date)
+                .is_some_and(|result| !result.finished && session.moves() >= result.moves);
+        DailyRun { official, ..run }
+    }
+}
+
+// 分享码中的移动编码：每步2位，每个字符3步
+const ALPHABET: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+
+fn checksum(text: &str) -> u32 {
+    // FNV-1a，只用于发现抄错或篡改后未重算的分享码
+    text.bytes()
+        .fold(0x811C_9DC5, |hash: u32, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+fn encode_moves(history: &[Direction]) -> String {
+    // 开头的数字是最后一个字符里的步数
+    let mut code = (history.len() % 3).to_string();
+    for chunk in history.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0, |bits, (i, &direction)| {
+            let index = Direction::ALL.iter().position(|&d| d == direction).unwrap();
+            bits | index << (2 * i)
+        });
+        code.push(ALPHABET[bits] as char);
+    }
+    code
+}
+
+fn decode_moves(code: &str) -> Result<Vec<Direction>, String> {
+    let mut chars = code.chars();
+    let tail = chars
+        .next()
+        .and_then(|c| c.to_digit(10))
+        .filter(|&tail| tail < 3)
+        .ok_or("分享码中的移动格式错误")? as usize;
+    let mut history = Vec::new();
+    for c in chars {
+        let bits = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or_else(|| format!("分享码中包含无效字符: {}", c))?;
+        history.extend((0..3).map(|i| Direction::ALL[(bits >> (2 * i)) & 3]));
+    }
+    if tail > 0 {
+        if history.is_empty() {
+            return Err("分享码中的移动格式错误".to_string());
+        }
+        history.truncate(history.len() - 3 + tail);
+    }
+    Ok(history)
+}
+
+// 分享码格式：日期.种子.分数.移动.校验和
+pub fn share_code(date: NaiveDate, session: &GameSession) -> String {
+    let body = format!(
+        "{}.{:x}.{}.{}",
+        date.format("%Y%m%d"),
+        session.seed(),
+        session.score(),
+        encode_moves(session.history())
+    );
+    let check = checksum(&body);
+    format!("{}.{:08x}", body, check)
+}
+
+// 验证通过的分享码，分数和最大方块都来自重放结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verified {
+    pub date: NaiveDate,
+    pub seed: u64,
+    pub score: u32,
+    pub max_tile: u32,
+    pub moves: usize,
+    pub finished: bool,
+}
+
+// 检查校验和与种子，再从当天的开局重放所有移动，分数必须与分享码一致
+pub fn verify(code: &str) -> Result<Verified, String> {
+    let code = code.trim();
+    let (body, check) = code.rsplit_once('.').ok_or("分享码格式错误")?;
+    if u32::from_str_radix(check, 16).ok() != Some(checksum(body)) {
+        return Err("分享码校验失败".to_string());
+    }
+    let parts: Vec<&str> = body.split('.').collect();
+    let [date, seed, score, moves] = parts[..] else {
+        return Err("分享码格式错误".to_string());
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| format!("无效日期: {}", date))?;
+    let seed = u64::from_str_radix(seed, 16).map_err(|_| format!("无效种子: {}", seed))?;
+    let score: u32 = score.parse().map_err(|_| format!("无效分数: {}", score))?;
+    if seed != daily_seed(date) {
+        return Err(format!("种子与 {} 的每日挑战不符", date));
+    }
+
+    let mut session = daily_session(date);
+    for (i, direction) in decode_moves(moves)?.into_iter().enumerate() {
+        if session.status() == GameStatus::Won {
+            session.continue_playing();
+        }
+        if !session.apply(direction) {
+            return Err(format!("第{}步无效", i + 1));
+        }
+    }
+    if session.score() != score {
+        return Err(format!("分数不符: 分享码为 {}，重放结果为 {}", score, session.score()));
+    }
+    Ok(Verified {
+        date,
+        seed,
+        score,
+        max_tile: session.board().max_tile(),
+        moves: session.moves(),
+        finished: session.is_game_over(),
+    })
+}
+
+// 每日挑战命令：rust_2048_game daily [--date YYYY-MM-DD] [--verify 分享码]
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut date = chrono::Local::now().date_naive();
+    let mut code = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--date" => date = parse_value(arg, iter.next())?,
+            "--verify" => code = Some(parse_value::<String>(arg, iter.next())?),
+            _ => return Err(format!("未知参数: {}", arg).into()),
+        }
+    }
+
+    match code {
+        Some(code) => {
+            let verified = verify(&code)?;
+            println!("验证通过: {} 的每日挑战", verified.date);
+            println!("分数: {}", verified.score);
+            println!("最大方块: {}", verified.max_tile);
+            println!("步数: {}{}", verified.moves, if verified.finished { "" } else { "（未结束）" });
+        }
+        None => {
+            println!("{} 的每日挑战种子: {}", date, daily_seed(date));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(date: NaiveDate, moves: usize) -> GameSession {
+        let mut session = daily_session(date);
+        for direction in Direction::ALL.into_iter().cycle().take(moves) {
+            session.apply(direction);
+        }
+        session
+    }
+
+    #[test]
+    fn test_daily_seed_depends_only_on_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(daily_seed(date), daily_seed(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()));
+        assert_ne!(daily_seed(date), daily_seed(date.succ_opt().unwrap()));
+        assert_eq!(daily_session(date).board(), daily_session(date).board());
+    }
+
+    #[test]
+    fn test_share_code_roundtrip() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        for moves in [0, 1, 2, 3, 40] {
+            let session = play(date, moves);
+            let code = share_code(date, &session);
+            let verified = verify(&code).unwrap();
+            assert_eq!(verified.score, session.score());
+            assert_eq!(verified.moves, session.moves());
+        }
+
+        // 改动分数并重算校验和，重放结果对不上
+        let session = play(date, 40);
+        let code = share_code(date, &session);
+        let fake_score = code.replacen(&format!(".{}.", session.score()), &format!(".{}.", session.score() + 4), 1);
+        assert!(verify(&fake_score).is_err());
+        let (body, _) = fake_score.rsplit_once('.').unwrap();
+        assert!(verify(&format!("{}.{:08x}", body, checksum(body))).unwrap_err().contains("分数不符"));
+
+        // 其他日期的种子不被接受
+        let other = share_code(date.succ_opt().unwrap(), &session);
+        assert!(verify(&other).is_err());
+    }
+
+    #[test]
+    fn test_one_official_attempt_per_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut history = DailyHistory::default();
+        let run = history.start(date).unwrap();
+        assert!(history.start(date).is_none());
+
+        let session = play(date, 10);
+        history.record(run, &session);
+        history.record(DailyRun { date, official: false }, &play(date, 3));
+        let result = history.get(date).unwrap();
+        assert_eq!(result.moves, session.moves());
+        assert_eq!(verify(&result.code).unwrap().score, session.score());
+    }
+}
@@ -0,0 +1,262 @@
+use eframe::egui;
+use egui::{Color32, Vec2};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use crate::board::Board;
+use crate::board_view::{self, BOARD_SIZE};
+use crate::merge::MergeRule;
+
+// 调色板中方块的个数，从变体最小的方块开始
+const PALETTE_SIZE: usize = 12;
+
+// 画笔：点击格子时放置的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Brush {
+    Empty,
+    Tile(u32),
+    Blocker,
+}
+
+// 编辑完成后交给游戏页面的操作
+pub enum EditorAction {
+    Play(Board),
+    Analyze(Board),
+}
+
+// 棋盘编辑器：左键用画笔设置格子，右键把格子循环到下一个方块，可以导入导出文本格式
+pub struct EditorView {
+    board: Board,
+    brush: Brush,
+    text: String,
+    message: Option<String>,
+}
+
+impl EditorView {
+    pub fn new() -> Self {
+        Self {
+            board: Board::from_cells([[0; 4]; 4]),
+            brush: Brush::Tile(2),
+            text: String::new(),
+            message: None,
+        }
+    }
+
+    // 先放所有出块数值，再从最大的一个往上合成；Threes 中1和2不能由合并得到，只能这样加入
+    fn palette(rule: MergeRule) -> Vec<u32> {
+        let mut palette = rule.spawn_values().to_vec();
+        palette.sort_unstable();
+        palette.dedup();
+        while palette.len() < PALETTE_SIZE {
+            match palette.last().and_then(|&tile| rule.next_tile(tile)) {
+                Some(next) => palette.push(next),
+                None => break,
+            }
+        }
+        palette
+    }
+
+    fn paint(&mut self, row: usize, col: usize, brush: Brush) {
+        match brush {
+            Brush::Empty => {
+                self.board.set_blocker(row, col, false);
+                self.board.set_cell(row, col, 0);
+            }
+            Brush::Tile(value) => {
+                self.board.set_blocker(row, col, false);
+                self.board.set_cell(row, col, value);
+            }
+            Brush::Blocker => self.board.set_blocker(row, col, true),
+        }
+    }
+
+    // 空格 -> 最小的方块 -> ... -> 调色板中最大的方块 -> 空格
+    fn cycle(&mut self, row: usize, col: usize, palette: &[u32]) {
+        let value = self.board.get_cell(row, col);
+        let next = if self.board.is_blocker(row, col) {
+            Brush::Empty
+        } else if value == 0 {
+            Brush::Tile(palette[0])
+        } else {
+            match palette.iter().position(|&tile| tile == value) {
+                Some(index) if index + 1 < palette.len() => Brush::Tile(palette[index + 1]),
+                _ => Brush::Empty,
+            }
+        };
+        self.paint(row, col, next);
+    }
+
+    fn clear(&mut self) {
+        self.board = Board::from_cells([[0; 4]; 4]).with_merge_rule(self.board.merge_rule());
+    }
+
+    // 随机放置若干个较小的方块，用于快速得到一个可玩的局面
+    fn randomize(&mut self, palette: &[u32]) {
+        let mut rng = rand::thread_rng();
+        self.clear();
+        let mut cells: Vec<(usize, usize)> = (0..16).map(|index| (index / 4, index % 4)).collect();
+        cells.shuffle(&mut rng);
+        let count = rng.gen_range(2..=12);
+        for &(row, col) in &cells[..count] {
+            self.board.set_cell(row, col, palette[rng.gen_range(0..palette.len().min(8))]);
+        }
+    }
+
+    fn import(&mut self) {
+        let score = self.board.score;
+        self.message = Some(match Board::parse_with_rule(&self.text, self.board.merge_rule()) {
+            Ok(mut board) => {
+                board.score = score;
+                self.board = board;
+                "已导入".to_string()
+            }
+            Err(e) => format!("导入失败: {}", e),
+        });
+    }
+
+    fn export(&mut self) {
+        self.text = self.board.to_string();
+        self.message = Some(match self.board.to_compact() {
+            Some(compact) => format!("紧凑格式: {}", compact),
+            None => "已导出".to_string(),
+        });
+    }
+
+    // 编辑器中的棋盘使用游戏设置里的变体，切换变体时清空棋盘
+    pub fn draw(&mut self, ui: &mut egui::Ui, rule: MergeRule) -> Option<EditorAction> {
+        if self.board.merge_rule() != rule {
+            self.board = Board::from_cells([[0; 4]; 4]).with_merge_rule(rule);
+            if let Brush::Tile(_) = self.brush {
+                self.brush = Brush::Tile(rule.spawn_values()[0]);
+            }
+        }
+        let palette = Self::palette(rule);
+        let mut action = None;
+
+        ui.vertical_centered(|ui| {
+            ui.label(format!("变体: {}（在游戏页面的设置中切换）", rule.label()));
+            ui.label("左键: 用画笔设置格子  右键: 循环到下一个方块");
+            ui.horizontal_wrapped(|ui| {
+                ui.label("画笔");
+                ui.selectable_value(&mut self.brush, Brush::Empty, "空格");
+                for &tile in &palette {
+                    ui.selectable_value(&mut self.brush, Brush::Tile(tile), tile.to_string());
+                }
+                ui.selectable_value(&mut self.brush, Brush::Blocker, "障碍");
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("清空").clicked() {
+                    self.clear();
+                }
+                if ui.button("随机").clicked() {
+                    self.randomize(&palette);
+                }
+                ui.label("分数");
+                ui.add(egui::DragValue::new(&mut self.board.score).speed(4));
+            });
+
+            let (rect, response) = ui.allocate_exact_size(Vec2::splat(BOARD_SIZE), egui::Sense::click());
+            board_view::draw_board(ui.painter(), rect, &self.board);
+            if let Some(pos) = response.interact_pointer_pos() {
+                let cell = (0..16)
+                    .map(|index| (index / 4, index % 4))
+                    .find(|&(row, col)| board_view::cell_rect(rect, row, col).contains(pos));
+                if let Some((row, col)) = cell {
+                    if response.clicked() {
+                        self.paint(row, col, self.brush);
+                    } else if response.secondary_clicked() {
+                        self.cycle(row, col, &palette);
+                    }
+                }
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("导入").clicked() {
+                    self.import();
+                }
+                if ui.button("导出").clicked() {
+                    self.export();
+                }
+            });
+            ui.add(
+                egui::TextEdit::multiline(&mut self.text)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_rows(4)
+                    .hint_text("4行4列的文本格式或16位紧凑格式"),
+            );
+            if let Some(message) = &self.message {
+                ui.label(message);
+            }
+
+            let playable = !self.board.is_game_over();
+            if !playable {
+                ui.colored_label(Color32::RED, "这个局面已经无路可走");
+            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(playable, egui::Button::new("从这里开始游戏")).clicked() {
+                    action = Some(EditorAction::Play(self.board.clone()));
+                }
+                if ui.add_enabled(playable, egui::Button::new("从这里分析")).clicked() {
+                    action = Some(EditorAction::Analyze(self.board.clone()));
+                }
+            });
+        });
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_and_paint() {
+        let mut editor = EditorView::new();
+        let palette = EditorView::palette(MergeRule::Classic);
+        editor.cycle(0, 0, &palette);
+        editor.cycle(0, 0, &palette);
+        assert_eq!(editor.board.get_cell(0, 0), 4);
+
+        editor.paint(0, 0, Brush::Tile(2048));
+        editor.cycle(0, 0, &palette);
+        assert_eq!(editor.board.get_cell(0, 0), 4096);
+        editor.cycle(0, 0, &palette);
+        assert_eq!(editor.board.get_cell(0, 0), 0);
+
+        editor.paint(1, 1, Brush::Blocker);
+        assert!(editor.board.is_blocker(1, 1));
+        editor.cycle(1, 1, &palette);
+        assert!(!editor.board.is_blocker(1, 1));
+
+        // Threes 的2不能由合并得到，也要能画出来
+        let palette = EditorView::palette(MergeRule::Threes);
+        assert_eq!(palette[..5], [1, 2, 3, 6, 12]);
+        assert_eq!(palette.len(), PALETTE_SIZE);
+        editor.paint(2, 2, Brush::Empty);
+        editor.cycle(2, 2, &palette);
+        editor.cycle(2, 2, &palette);
+        assert_eq!(editor.board.get_cell(2, 2), 2);
+        editor.cycle(2, 2, &palette);
+        assert_eq!(editor.board.get_cell(2, 2), 3);
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let mut editor = EditorView::new();
+        editor.randomize(&EditorView::palette(MergeRule::Classic));
+        editor.paint(3, 3, Brush::Blocker);
+        editor.board.score = 1234;
+        let board = editor.board.clone();
+
+        editor.export();
+        editor.clear();
+        editor.import();
+        assert_eq!(editor.board.to_cells(), board.to_cells());
+        assert!(editor.board.is_blocker(3, 3));
+
+        editor.text = "2 2\n".to_string();
+        editor.import();
+        assert!(editor.message.as_deref().unwrap().starts_with("导入失败"));
+    }
+}
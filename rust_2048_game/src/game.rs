@@ -0,0 +1,261 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::board::{Board, Direction};
+use crate::cube::{CubeBoard, CubeDirection};
+use crate::hex::{HexBoard, HexDirection};
+use crate::merge::MergeRule;
+use crate::ntuple::{self, NTupleStrategy};
+use crate::strategy::Strategy;
+
+// 不同形状棋盘的共同接口，自动玩家和无界面运行通过它操作方形、六边形和立方体棋盘
+pub trait Game: Clone + PartialEq + Send + Sync + 'static {
+    type Move: Copy + Send + Sync + fmt::Debug + 'static;
+
+    // 所有移动方向，策略按这个顺序给出评估值
+    const MOVES: &'static [Self::Move];
+
+    fn new_game<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    // 只滑动合并，不生成新方块；返回棋盘是否发生变化
+    fn slide(&mut self, direction: Self::Move) -> bool;
+
+    fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R);
+
+    fn score(&self) -> u32;
+
+    fn max_tile(&self) -> u32;
+
+    fn is_game_over(&self) -> bool;
+
+    // 训练好的 n-tuple 网络只针对方形棋盘
+    fn ntuple_strategy(_weights: &str) -> Result<Box<dyn Strategy<Self>>, String> {
+        Err("n-tuple 策略只支持方形棋盘".to_string())
+    }
+}
+
+impl Game for Board {
+    type Move = Direction;
+    const MOVES: &'static [Direction] = &Direction::ALL;
+
+    fn new_game<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Board::new_with_rng(rng)
+    }
+
+    fn slide(&mut self, direction: Direction) -> bool {
+        Board::slide(self, direction)
+    }
+
+    fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        Board::add_random_tile(self, rng)
+    }
+
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn max_tile(&self) -> u32 {
+        Board::max_tile(self)
+    }
+
+    fn is_game_over(&self) -> bool {
+        Board::is_game_over(self)
+    }
+
+    fn ntuple_strategy(weights: &str) -> Result<Box<dyn Strategy<Self>>, String> {
+        let network =
+            ntuple::load_cached(weights).map_err(|e| format!("读取权重文件 {} 失败: {}", weights, e))?;
+        Ok(Box::new(NTupleStrategy::new(network)))
+    }
+}
+
+impl Game for HexBoard {
+    type Move = HexDirection;
+    const MOVES: &'static [HexDirection] = &HexDirection::ALL;
+
+    fn new_game<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        HexBoard::new_with_rng(rng)
+    }
+
+    fn slide(&mut self, direction: HexDirection) -> bool {
+        HexBoard::slide(self, direction)
+    }
+
+    fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        HexBoard::add_random_tile(self, rng)
+    }
+
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn max_tile(&self) -> u32 {
+        HexBoard::max_tile(self)
+    }
+
+    fn is_game_over(&self) -> bool {
+        HexBoard::is_game_over(self)
+    }
+}
+
+impl Game for CubeBoard {
+    type Move = CubeDirection;
+    const MOVES: &'static [CubeDirection] = &CubeDirection::ALL;
+
+    fn new_game<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        CubeBoard::new_with_rng(rng)
+    }
+
+    fn slide(&mut self, direction: CubeDirection) -> bool {
+        CubeBoard::slide(self, direction)
+    }
+
+    fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        CubeBoard::add_random_tile(self, rng)
+    }
+
+    fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn max_tile(&self) -> u32 {
+        CubeBoard::max_tile(self)
+    }
+
+    fn is_game_over(&self) -> bool {
+        CubeBoard::is_game_over(self)
+    }
+}
+
+// 一局由种子决定出块的游戏，不记录历史；方形棋盘的完整功能见 GameSession
+pub struct SeededGame<G: Game> {
+    seed: u64,
+    board: G,
+    moves: usize,
+    rng: StdRng,
+}
+
+impl<G: Game> SeededGame<G> {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self {
+            seed,
+            board: G::new_game(&mut rng),
+            moves: 0,
+            rng,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn board(&self) -> &G {
+        &self.board
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    pub fn score(&self) -> u32 {
+        self.board.score()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.board.is_game_over()
+    }
+
+    pub fn apply(&mut self, direction: G::Move) -> bool {
+        let moved = self.board.slide(direction);
+        if moved {
+            self.board.add_random_tile(&mut self.rng);
+            self.moves += 1;
+        }
+        moved
+    }
+}
+
+// 一局进行中的游戏：方形棋盘是 GameSession，其它形状是 SeededGame；无界面运行通过它使用同一个对局循环
+pub trait Playable {
+    type Board: Game;
+
+    fn seed(&self) -> u64;
+
+    fn board(&self) -> &Self::Board;
+
+    fn moves(&self) -> usize;
+
+    fn is_game_over(&self) -> bool;
+
+    fn has_won(&self) -> bool;
+
+    // 自动玩家走下一步之前调用，elapsed 为距离上一步的秒数
+    fn before_move(&mut self, _elapsed: f64) {}
+
+    fn apply(&mut self, direction: <Self::Board as Game>::Move) -> bool;
+}
+
+impl<G: Game> Playable for SeededGame<G> {
+    type Board = G;
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn board(&self) -> &G {
+        &self.board
+    }
+
+    fn moves(&self) -> usize {
+        self.moves
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.board.is_game_over()
+    }
+
+    // 其它形状只使用经典规则，目标与经典2048相同
+    fn has_won(&self) -> bool {
+        self.board.max_tile() >= MergeRule::Classic.default_target()
+    }
+
+    fn apply(&mut self, direction: G::Move) -> bool {
+        SeededGame::apply(self, direction)
+    }
+}
+
+// 命令行和界面中选择的棋盘形状
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shape {
+    #[default]
+    Square,
+    Hex,
+    Cube,
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Shape::Square => "square",
+            Shape::Hex => "hex",
+            Shape::Cube => "cube",
+        })
+    }
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "square" => Ok(Shape::Square),
+            "hex" => Ok(Shape::Hex),
+            "cube" => Ok(Shape::Cube),
+            other => Err(format!("未知棋盘形状: {}", other)),
+        }
+    }
+}
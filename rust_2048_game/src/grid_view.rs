@@ -0,0 +1,256 @@
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use egui::{Color32, FontId, Pos2, Rect, Vec2};
+
+use crate::board_view::{self, BOARD_SIZE};
+use crate::rules::GameStatus;
+use crate::run_control::RunControl;
+use crate::session::GameSession;
+use crate::stats::GameStats;
+use crate::strategy::{Strategy, StrategyKind};
+use crate::strategy_view;
+
+// 可选的网格边长，对应4、9、16个棋盘
+const GRID_SIZES: [usize; 3] = [2, 3, 4];
+// 极速模式下一帧最多占用的时间，与游戏页面相同
+const FRAME_BUDGET: Duration = Duration::from_millis(12);
+// 每个棋盘上方标签的高度
+const LABEL_HEIGHT: f32 = 16.0;
+const GAP: f32 = 8.0;
+
+// 网格中的一个棋盘，有自己的种子和策略实例
+struct GridBoard {
+    session: GameSession,
+    strategy: Box<dyn Strategy>,
+    games: u32,
+}
+
+// 多棋盘页面：同时自动运行多局经典规则的游戏，用于长时间观察策略表现
+pub struct GridView {
+    size: usize,
+    boards: Vec<GridBoard>,
+    strategy_kind: StrategyKind,
+    strategy_error: Option<String>,
+    control: RunControl,
+    // 每开始一局递增，保证所有棋盘的种子都不相同
+    next_seed: u64,
+    stats: GameStats,
+    best_tile: u32,
+    total_moves: u64,
+    started_at: Option<f64>,
+    enlarged: Option<usize>,
+    // 一轮中下一个要走的棋盘；超出一帧的时间预算时下一帧从这里接着走
+    next_board: usize,
+}
+
+impl GridView {
+    pub fn new() -> Self {
+        let mut view = Self {
+            size: 2,
+            boards: Vec::new(),
+            strategy_kind: StrategyKind::Random,
+            strategy_error: None,
+            control: RunControl::new(0.2),
+            next_seed: rand::random::<u32>() as u64,
+            stats: GameStats::default(),
+            best_tile: 0,
+            total_moves: 0,
+            started_at: None,
+            enlarged: None,
+            next_board: 0,
+        };
+        view.restart();
+        view
+    }
+
+    fn new_session(&mut self) -> GameSession {
+        self.next_seed += 1;
+        GameSession::new(self.next_seed)
+    }
+
+    fn build_strategy(&mut self) -> Option<Box<dyn Strategy>> {
+        match self.strategy_kind.build(rand::random()) {
+            Ok(strategy) => Some(strategy),
+            Err(e) => {
+                self.strategy_error = Some(e);
+                None
+            }
+        }
+    }
+
+    // 按当前的网格大小和策略重新开始所有棋盘，累计统计清零；
+    // 策略创建失败时清空棋盘，不保留按旧的网格大小排列的棋盘
+    fn restart(&mut self) {
+        self.strategy_error = None;
+        let boards: Option<Vec<GridBoard>> = (0..self.size * self.size)
+            .map(|_| {
+                Some(GridBoard {
+                    strategy: self.build_strategy()?,
+                    session: self.new_session(),
+                    games: 0,
+                })
+            })
+            .collect();
+        self.boards = boards.unwrap_or_default();
+        self.stats = GameStats::default();
+        self.best_tile = 0;
+        self.total_moves = 0;
+        self.started_at = None;
+        self.enlarged = None;
+        self.next_board = 0;
+    }
+
+    pub fn repaint_after(&self, now: f64) -> Option<Duration> {
+        self.control.repaint_after(now)
+    }
+
+    // 每个到期的步骤让所有棋盘各走一步，结束的棋盘计入统计后换新种子重新开始；
+    // 每走一个棋盘检查一次时间预算，耗时的策略不会让一帧卡住太久
+    pub fn update(&mut self, now: f64) {
+        if self.boards.is_empty() {
+            return;
+        }
+        let mut rounds = self.control.moves_due(now);
+        if rounds > 0 && self.started_at.is_none() {
+            self.started_at = Some(now);
+        }
+        let started = Instant::now();
+        // 上一帧没走完的一轮先走完，暂停或触发停止条件后不再继续
+        while rounds > 0 || (self.next_board > 0 && !self.control.paused) {
+            let index = self.next_board;
+            self.next_board = (index + 1) % self.boards.len();
+            if self.next_board == 0 {
+                rounds = rounds.saturating_sub(1);
+            }
+            if self.step(index) || started.elapsed() >= FRAME_BUDGET {
+                return;
+            }
+        }
+    }
+
+    // 返回是否触发了运行控制里的停止条件
+    fn step(&mut self, index: usize) -> bool {
+        if self.boards[index].session.is_game_over() {
+            let score = self.boards[index].session.score();
+            self.stats.record(score);
+            let session = self.new_session();
+            let board = &mut self.boards[index];
+            board.session = session;
+            board.games += 1;
+            return self.control.game_finished();
+        }
+
+        let board = &mut self.boards[index];
+        // 自动运行达到目标后直接继续
+        if board.session.status() == GameStatus::Won {
+            board.session.continue_playing();
+        }
+        if let Some(direction) = board.strategy.choose(board.session.board()) {
+            board.session.apply(direction);
+            self.total_moves += 1;
+        }
+        let max_tile = board.session.board().max_tile();
+        self.best_tile = self.best_tile.max(max_tile);
+        self.control.check_target(max_tile)
+    }
+
+    fn board_label(&self, index: usize) -> String {
+        let board = &self.boards[index];
+        format!(
+            "#{} {} 种子{} 分数{}",
+            index + 1,
+            board.strategy.name(),
+            board.session.seed(),
+            board.session.score()
+        )
+    }
+
+    fn draw_stats(&self, ui: &mut egui::Ui, now: f64) {
+        let current: u32 = self.boards.iter().map(|board| board.session.score()).sum();
+        ui.label(format!(
+            "完成局数: {}  平均分数: {:.1}  最高分数: {}  最大方块: {}",
+            self.stats.total_games,
+            self.stats.average().unwrap_or(0.0),
+            self.stats.max_score,
+            self.best_tile
+        ));
+        let elapsed = self.started_at.map_or(0.0, |start| now - start);
+        let speed = if elapsed > 0.0 { self.total_moves as f64 / elapsed } else { 0.0 };
+        ui.label(format!(
+            "当前总分: {}  总步数: {}  每秒步数: {:.0}",
+            current, self.total_moves, speed
+        ));
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, now: f64) {
+        ui.vertical_centered(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("棋盘数");
+                let size = self.size;
+                for grid in GRID_SIZES {
+                    ui.selectable_value(&mut self.size, grid, (grid * grid).to_string());
+                }
+                if self.size != size {
+                    self.restart();
+                }
+                if ui.button("重新开始").clicked() {
+                    self.restart();
+                }
+            });
+            if strategy_view::draw(ui, &mut self.strategy_kind) {
+                self.restart();
+            }
+            if let Some(e) = &self.strategy_error {
+                ui.colored_label(Color32::RED, e);
+            }
+            self.control.draw(ui);
+            self.draw_stats(ui, now);
+        });
+
+        if let Some(index) = self.enlarged.filter(|&index| index < self.boards.len()) {
+            ui.vertical_centered(|ui| {
+                ui.label(self.board_label(index));
+                ui.label(format!(
+                    "已完成{}局  步数: {}",
+                    self.boards[index].games,
+                    self.boards[index].session.moves()
+                ));
+                if ui.button("返回网格").clicked() {
+                    self.enlarged = None;
+                }
+            });
+            let rect = board_view::board_rect(ui);
+            board_view::draw_board(ui.painter(), rect, self.boards[index].session.board());
+            let response = ui.interact(rect, ui.id().with("grid_enlarged"), egui::Sense::click());
+            if response.clicked() {
+                self.enlarged = None;
+            }
+            return;
+        }
+
+        // 按可用空间缩放，每个棋盘上方留出标签的位置
+        let available = ui.available_size();
+        let cell = ((available.x.min(available.y) - GAP) / self.size as f32 - GAP - LABEL_HEIGHT).clamp(40.0, BOARD_SIZE);
+        let width = self.size as f32 * (cell + GAP) - GAP;
+        let origin = Pos2::new(ui.max_rect().center().x - width / 2.0, ui.cursor().min.y + GAP);
+        for index in 0..self.boards.len() {
+            let (row, col) = (index / self.size, index % self.size);
+            let top_left = origin + Vec2::new(col as f32 * (cell + GAP), row as f32 * (cell + GAP + LABEL_HEIGHT));
+            let rect = Rect::from_min_size(top_left + Vec2::new(0.0, LABEL_HEIGHT), Vec2::splat(cell));
+            ui.painter().text(
+                top_left,
+                egui::Align2::LEFT_TOP,
+                self.board_label(index),
+                FontId::proportional(11.0),
+                ui.visuals().text_color(),
+            );
+            board_view::draw_board(ui.painter(), rect, self.boards[index].session.board());
+            let response = ui.interact(rect, ui.id().with(("grid_board", index)), egui::Sense::click());
+            if response.on_hover_text("点击放大").clicked() {
+                self.enlarged = Some(index);
+            }
+        }
+        ui.allocate_space(Vec2::new(available.x, self.size as f32 * (cell + GAP + LABEL_HEIGHT)));
+    }
+}
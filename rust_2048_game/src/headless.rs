@@ -0,0 +1,312 @@
+use std::time::Instant;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cube::CubeBoard;
+use crate::game::{Game, Playable, SeededGame, Shape};
+use crate::heatmap::PositionStats;
+use crate::hex::HexBoard;
+use crate::highscores::{GameRecord, HighScoreStore};
+use crate::rules::{Challenge, GameRules};
+use crate::session::GameSession;
+use crate::spawner::{SpawnPolicy, SpawnRules};
+use crate::strategy::{Strategy, StrategyKind};
+use crate::stats::GameStats;
+
+// 无界面批量运行：rust_2048_game headless [选项]
+pub struct HeadlessOptions {
+    pub games: u32,
+    pub seed: u64,
+    pub max_moves: Option<usize>,
+    pub json: bool,
+    pub save: Option<String>,
+    pub load: Option<String>,
+    // 把已结束的对局写入本地最高分记录
+    pub record: bool,
+    // 位置统计的导出文件，格式由扩展名决定
+    pub heatmap: Option<String>,
+    pub strategy: StrategyKind,
+    pub spawn: SpawnPolicy,
+    pub spawn_rules: SpawnRules,
+    pub rules: GameRules,
+    pub shape: Shape,
+}
+
+impl HeadlessOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = HeadlessOptions {
+            games: 10,
+            seed: rand::random(),
+            max_moves: None,
+            json: false,
+            save: None,
+            load: None,
+            record: false,
+            heatmap: None,
+            strategy: StrategyKind::Random,
+            spawn: SpawnPolicy::Random,
+            spawn_rules: SpawnRules::CLASSIC,
+            rules: GameRules::default(),
+            shape: Shape::Square,
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--games" => options.games = parse_value(arg, iter.next())?,
+                "--seed" => options.seed = parse_value(arg, iter.next())?,
+                "--max-moves" => options.max_moves = Some(parse_value(arg, iter.next())?),
+                "--json" => options.json = true,
+                "--save" => options.save = Some(parse_value(arg, iter.next())?),
+                "--load" => options.load = Some(parse_value(arg, iter.next())?),
+                "--record" => options.record = true,
+                "--heatmap" => options.heatmap = Some(parse_value(arg, iter.next())?),
+                "--spawn" => options.spawn = parse_value(arg, iter.next())?,
+                "--shape" => options.shape = parse_value(arg, iter.next())?,
+                arg if SpawnRules::ARGS.contains(&arg) => options.spawn_rules.parse_arg(arg, &mut iter)?,
+                arg if GameRules::ARGS.contains(&arg) => options.rules.parse_arg(arg, &mut iter)?,
+                arg if StrategyKind::ARGS.contains(&arg) => options.strategy.parse_arg(arg, &mut iter)?,
+                _ => return Err(format!("未知参数: {}", arg)),
+            }
+        }
+        // 没有指定出块权重时使用变体的默认权重
+        if !args.iter().any(|arg| arg == "--spawn-weights") {
+            options.spawn_rules.weights = options.rules.merge.default_spawn_weights();
+        }
+        Ok(options)
+    }
+}
+
+pub fn parse_value<T: std::str::FromStr>(name: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("参数 {} 缺少取值", name))?;
+    value.parse().map_err(|_| format!("参数 {} 的取值无效: {}", name, value))
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GameResult {
+    pub seed: u64,
+    pub score: u32,
+    pub moves: usize,
+    pub max_tile: u32,
+    pub won: bool,
+    pub finished: bool,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct HeadlessReport {
+    pub stats: GameStats,
+    pub games: Vec<GameResult>,
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let options = HeadlessOptions::parse(args)?;
+    match options.shape {
+        Shape::Square => run_square(&options),
+        Shape::Hex => run_shape::<HexBoard>(&options),
+        Shape::Cube => run_shape::<CubeBoard>(&options),
+    }
+}
+
+// 方形棋盘支持全部规则、存档、最高分记录和位置统计
+fn run_square(options: &HeadlessOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let mut load = options.load.as_deref();
+    let mut records = HighScoreStore::default();
+    let mut positions = PositionStats::default();
+
+    let (report, last_session) = run_games(
+        options,
+        |seed| match load.take() {
+            Some(path) => load_session(path),
+            None => Ok(GameSession::with_rules(seed, options.rules, options.spawn_rules).with_spawn_policy(options.spawn)),
+        },
+        |session: &GameSession, player, duration_secs| {
+            if options.heatmap.is_some() {
+                positions.record_game(&session.boards());
+            }
+            if session.is_game_over() {
+                records.add(GameRecord {
+                    score: session.score(),
+                    max_tile: session.board().max_tile(),
+                    moves: session.moves(),
+                    duration_secs,
+                    player: player.to_string(),
+                    seed: session.seed(),
+                    date: chrono::Local::now(),
+                    merge: options.rules.merge,
+                    challenge: options.rules.challenge,
+                    target: options.rules.target,
+                });
+            }
+        },
+    )?;
+
+    if let (Some(path), Some(session)) = (&options.save, &last_session) {
+        save_session(session, path)?;
+    }
+    if options.record {
+        save_records(records)?;
+    }
+
+    print_report(&report, options)?;
+    if let Some(path) = &options.heatmap {
+        positions.export(path)?;
+        if !options.json {
+            println!(
+                "位置统计已导出到 {}，最大方块在角落: {:.1}%，结束时在角落: {:.1}%",
+                path,
+                positions.corner_rate().unwrap_or(0.0) * 100.0,
+                positions.final_corner_rate().unwrap_or(0.0) * 100.0
+            );
+        }
+    }
+    Ok(())
+}
+
+// 六边形和立方体棋盘只使用经典规则，不支持存档、最高分记录和位置统计
+fn run_shape<G: Game>(options: &HeadlessOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if options.save.is_some() || options.load.is_some() || options.record || options.heatmap.is_some() {
+        return Err("--save、--load、--record 和 --heatmap 只支持方形棋盘".into());
+    }
+    if options.spawn != SpawnPolicy::Random
+        || options.spawn_rules != SpawnRules::CLASSIC
+        || options.rules != GameRules::default()
+    {
+        return Err(format!("{} 棋盘只支持经典规则，不能使用 --target、--variant、--challenge 和出块设置", options.shape).into());
+    }
+
+    let (report, _) = run_games(options, |seed| Ok(SeededGame::<G>::new(seed)), |_, _, _| {})?;
+    print_report(&report, options)
+}
+
+// 所有形状共用的对局循环：每局用新的种子开始，结束后把对局、策略名称和用时交给 finish，返回报告和最后一局
+fn run_games<P: Playable>(
+    options: &HeadlessOptions,
+    mut new_game: impl FnMut(u64) -> Result<P, Box<dyn std::error::Error>>,
+    mut finish: impl FnMut(&P, &str, f64),
+) -> Result<(HeadlessReport, Option<P>), Box<dyn std::error::Error>> {
+    let mut report = HeadlessReport {
+        stats: GameStats::default(),
+        games: Vec::new(),
+    };
+    let mut last_game = None;
+
+    for index in 0..options.games {
+        let mut game = new_game(options.seed.wrapping_add(index as u64))?;
+        let started = Instant::now();
+        let mut strategy = options.strategy.build::<P::Board>(game.seed())?;
+        play(&mut game, strategy.as_mut(), options.max_moves);
+        finish(&game, strategy.name(), started.elapsed().as_secs_f64());
+
+        let board = game.board();
+        let finished = game.is_game_over();
+        if finished {
+            report.stats.record(board.score());
+        }
+        report.games.push(GameResult {
+            seed: game.seed(),
+            score: board.score(),
+            moves: game.moves(),
+            max_tile: board.max_tile(),
+            won: game.has_won(),
+            finished,
+        });
+        last_game = Some(game);
+    }
+    Ok((report, last_game))
+}
+
+fn print_report(report: &HeadlessReport, options: &HeadlessOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if options.json {
+        print_json(report)?;
+    } else {
+        for game in &report.games {
+            println!(
+                "种子 {:>20}  分数 {:>6}  步数 {:>5}  最大方块 {:>5}{}{}",
+                game.seed,
+                game.score,
+                game.moves,
+                game.max_tile,
+                if game.won { "  达成目标" } else { "" },
+                if game.finished { "" } else { "  (未结束)" }
+            );
+        }
+        if options.rules.challenge != Challenge::Endless {
+            println!("挑战模式: {}", options.rules.challenge.label());
+        }
+        println!(
+            "总场次: {}, 平均分数: {:.1}, 最高分数: {}, 达成{}: {}/{}",
+            report.stats.total_games,
+            report.stats.average().unwrap_or(0.0),
+            report.stats.max_score,
+            options.rules.target,
+            report.games.iter().filter(|game| game.won).count(),
+            report.games.len()
+        );
+    }
+    Ok(())
+}
+
+fn play<P: Playable>(game: &mut P, strategy: &mut dyn Strategy<P::Board>, max_moves: Option<usize>) {
+    let mut last = Instant::now();
+    while !game.is_game_over() {
+        if max_moves.is_some_and(|max| game.moves() >= max) {
+            break;
+        }
+        let Some(direction) = strategy.choose(game.board()) else {
+            break;
+        };
+        let now = Instant::now();
+        game.before_move((now - last).as_secs_f64());
+        last = now;
+        game.apply(direction);
+    }
+}
+
+#[cfg(feature = "serde")]
+fn print_json(report: &HeadlessReport) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn save_session(session: &GameSession, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    session.save(path)
+}
+
+#[cfg(feature = "serde")]
+fn load_session(path: &str) -> Result<GameSession, Box<dyn std::error::Error>> {
+    GameSession::load(path)
+}
+
+#[cfg(feature = "serde")]
+fn save_records(records: HighScoreStore) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::highscores::HIGHSCORE_FILE;
+    use crate::storage;
+
+    let mut store: HighScoreStore = storage::load_json(HIGHSCORE_FILE)?.unwrap_or_default();
+    for record in records.records {
+        store.add(record);
+    }
+    storage::save_json(HIGHSCORE_FILE, &store)
+}
+
+#[cfg(not(feature = "serde"))]
+fn save_records(_records: HighScoreStore) -> Result<(), Box<dyn std::error::Error>> {
+    Err("保存最高分记录需要启用serde特性".into())
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_report: &HeadlessReport) -> Result<(), Box<dyn std::error::Error>> {
+    Err("JSON输出需要启用serde特性".into())
+}
+
+#[cfg(not(feature = "serde"))]
+fn save_session(_session: &GameSession, _path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    Err("保存游戏需要启用serde特性".into())
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_session(_path: &str) -> Result<GameSession, Box<dyn std::error::Error>> {
+    Err("读取游戏需要启用serde特性".into())
+}
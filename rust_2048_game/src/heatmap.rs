@@ -0,0 +1,278 @@
+use std::fmt::Write as _;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+
+// 按步数把一局分成若干阶段，观察最大方块的位置随时间的变化；最后一个阶段包含之后的所有步
+pub const PHASE_MOVES: usize = 100;
+pub const PHASES: usize = 10;
+
+#[cfg(feature = "serde")]
+pub const POSITIONS_FILE: &str = "positions.json";
+
+const CORNERS: [(usize, usize); 4] = [(0, 0), (0, 3), (3, 0), (3, 3)];
+
+pub type Grid = [[u64; 4]; 4];
+
+// 多局游戏的位置统计：每一步之后的棋盘都是一个样本
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PositionStats {
+    pub games: u32,
+    pub samples: u64,
+    // 格子上有方块的样本数
+    pub occupancy: Grid,
+    // 最大方块在这个格子上的样本数
+    pub max_tile: Grid,
+    // 最大方块在角落的样本数
+    pub corner: u64,
+    // 结束时最大方块在角落的局数
+    pub final_corner: u32,
+    pub phase_samples: [u64; PHASES],
+    pub phase_max_tile: [Grid; PHASES],
+}
+
+// 最大方块所在的格子，有多个时优先取角落上的
+fn max_tile_cell(board: &Board) -> Option<(usize, usize)> {
+    let max = board.max_tile();
+    if max == 0 {
+        return None;
+    }
+    let cells = (0..16).map(|index| (index / 4, index % 4));
+    let mut positions = cells.filter(|&(row, col)| board.get_cell(row, col) == max);
+    let first = positions.next()?;
+    Some(positions.chain([first]).find(|cell| CORNERS.contains(cell)).unwrap_or(first))
+}
+
+impl PositionStats {
+    pub fn record(&mut self, board: &Board, moves: usize) {
+        self.samples += 1;
+        for row in 0..4 {
+            for col in 0..4 {
+                if board.get_cell(row, col) != 0 {
+                    self.occupancy[row][col] += 1;
+                }
+            }
+        }
+        let phase = (moves / PHASE_MOVES).min(PHASES - 1);
+        self.phase_samples[phase] += 1;
+        if let Some((row, col)) = max_tile_cell(board) {
+            self.max_tile[row][col] += 1;
+            self.phase_max_tile[phase][row][col] += 1;
+            if CORNERS.contains(&(row, col)) {
+                self.corner += 1;
+            }
+        }
+    }
+
+    // 一局结束时调用，样本在每一步之后已经用 record 记录
+    pub fn finish_game(&mut self, board: &Board) {
+        self.games += 1;
+        if max_tile_cell(board).is_some_and(|cell| CORNERS.contains(&cell)) {
+            self.final_corner += 1;
+        }
+    }
+
+    // 记录一整局：开局和每一步之后的棋盘，见 GameSession::boards
+    pub fn record_game(&mut self, boards: &[Board]) {
+        for (moves, board) in boards.iter().enumerate() {
+            self.record(board, moves);
+        }
+        if let Some(last) = boards.last() {
+            self.finish_game(last);
+        }
+    }
+
+    pub fn merge(&mut self, other: &PositionStats) {
+        self.games += other.games;
+        self.samples += other.samples;
+        self.corner += other.corner;
+        self.final_corner += other.final_corner;
+        add_grid(&mut self.occupancy, &other.occupancy);
+        add_grid(&mut self.max_tile, &other.max_tile);
+        for phase in 0..PHASES {
+            self.phase_samples[phase] += other.phase_samples[phase];
+            add_grid(&mut self.phase_max_tile[phase], &other.phase_max_tile[phase]);
+        }
+    }
+
+    // 阶段为 None 时统计全部样本
+    pub fn max_tile_grid(&self, phase: Option<usize>) -> (&Grid, u64) {
+        match phase {
+            Some(phase) => (&self.phase_max_tile[phase], self.phase_samples[phase]),
+            None => (&self.max_tile, self.samples),
+        }
+    }
+
+    pub fn corner_rate(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.corner as f64 / self.samples as f64)
+    }
+
+    pub fn final_corner_rate(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.final_corner as f64 / self.games as f64)
+    }
+
+    // 文本报告：每个格子的比例排成4x4网格
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "局数: {}  样本数: {}", self.games, self.samples);
+        let _ = writeln!(
+            text,
+            "最大方块在角落: {:.1}%  结束时在角落: {:.1}%",
+            self.corner_rate().unwrap_or(0.0) * 100.0,
+            self.final_corner_rate().unwrap_or(0.0) * 100.0
+        );
+        write_grid(&mut text, "格子占用率", &self.occupancy, self.samples);
+        write_grid(&mut text, "最大方块位置", &self.max_tile, self.samples);
+        for phase in 0..PHASES {
+            if self.phase_samples[phase] > 0 {
+                let title = format!("最大方块位置（{}）", phase_label(phase));
+                write_grid(&mut text, &title, &self.phase_max_tile[phase], self.phase_samples[phase]);
+            }
+        }
+        text
+    }
+
+    // CSV：每行一个格子，阶段为空表示全部样本
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,phase,row,col,count,samples,rate\n");
+        let mut write_rows = |kind: &str, phase: Option<usize>, grid: &Grid, samples: u64| {
+            for (row, counts) in grid.iter().enumerate() {
+                for (col, &count) in counts.iter().enumerate() {
+                    let rate = if samples > 0 { count as f64 / samples as f64 } else { 0.0 };
+                    let phase = phase.map_or(String::new(), |phase| phase.to_string());
+                    let _ = writeln!(csv, "{},{},{},{},{},{},{:.4}", kind, phase, row, col, count, samples, rate);
+                }
+            }
+        };
+        write_rows("occupancy", None, &self.occupancy, self.samples);
+        write_rows("max_tile", None, &self.max_tile, self.samples);
+        for phase in 0..PHASES {
+            write_rows("max_tile", Some(phase), &self.phase_max_tile[phase], self.phase_samples[phase]);
+        }
+        csv
+    }
+
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn to_json(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Err("导出JSON需要启用 serde 特性".into())
+    }
+
+    // 按扩展名选择格式：.csv 为CSV，.json 为JSON（需要 serde 特性），其它为文本
+    pub fn export(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let content = if path.ends_with(".csv") {
+            self.to_csv()
+        } else if path.ends_with(".json") {
+            self.to_json()?
+        } else {
+            self.to_text()
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+pub fn phase_label(phase: usize) -> String {
+    if phase + 1 == PHASES {
+        format!("第{}步以后", phase * PHASE_MOVES)
+    } else {
+        format!("第{}-{}步", phase * PHASE_MOVES, (phase + 1) * PHASE_MOVES - 1)
+    }
+}
+
+fn add_grid(grid: &mut Grid, other: &Grid) {
+    for row in 0..4 {
+        for col in 0..4 {
+            grid[row][col] += other[row][col];
+        }
+    }
+}
+
+fn write_grid(text: &mut String, title: &str, grid: &Grid, samples: u64) {
+    let _ = writeln!(text, "\n{}:", title);
+    for row in grid {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|&count| {
+                let rate = if samples > 0 { count as f64 / samples as f64 } else { 0.0 };
+                format!("{:>6.1}%", rate * 100.0)
+            })
+            .collect();
+        let _ = writeln!(text, "{}", cells.join(" "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    fn test_record_positions() {
+        let mut stats = PositionStats::default();
+        let corner = Board::from_cells([[8, 2, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]);
+        let middle = Board::from_cells([[2, 0, 0, 0], [0, 16, 0, 0], [0, 0, 0, 0], [0, 0, 0, 16]]);
+        stats.record_game(&[corner.clone(), middle.clone()]);
+        stats.record_game(&[middle]);
+
+        assert_eq!(stats.games, 2);
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.occupancy[0][0], 3);
+        assert_eq!(stats.occupancy[1][1], 2);
+        // 两个16时取角落上的那个
+        assert_eq!(stats.max_tile[3][3], 2);
+        assert_eq!(stats.max_tile[0][0], 1);
+        assert_eq!(stats.corner_rate(), Some(1.0));
+        assert_eq!(stats.final_corner_rate(), Some(1.0));
+        assert_eq!(stats.phase_samples[0], 3);
+
+        let mut merged = PositionStats::default();
+        merged.merge(&stats);
+        merged.merge(&stats);
+        assert_eq!(merged.samples, 6);
+        assert_eq!(merged.max_tile[3][3], 4);
+    }
+
+    #[test]
+    fn test_incremental_matches_whole_game() {
+        let mut session = crate::session::GameSession::new(7);
+        let mut incremental = PositionStats::default();
+        incremental.record(session.board(), 0);
+        for direction in [Direction::Left, Direction::Up, Direction::Right, Direction::Down].repeat(20) {
+            if session.apply(direction) {
+                incremental.record(session.board(), session.moves());
+            }
+        }
+        incremental.finish_game(session.board());
+
+        let mut whole = PositionStats::default();
+        whole.record_game(&session.boards());
+        assert_eq!(incremental, whole);
+        assert_eq!(whole.games, 1);
+        assert_eq!(whole.samples, session.moves() as u64 + 1);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&whole).unwrap();
+            assert_eq!(serde_json::from_str::<PositionStats>(&json).unwrap(), whole);
+        }
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let mut stats = PositionStats::default();
+        stats.record(&Board::from_cells([[0, 0, 0, 0], [0, 4, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]), 250);
+        let csv = stats.to_csv();
+        assert_eq!(csv.lines().count(), 1 + 16 * (2 + PHASES));
+        assert!(csv.contains("max_tile,,1,1,1,1,1.0000"));
+        assert!(csv.contains("max_tile,2,1,1,1,1,1.0000"));
+        assert_eq!(stats.corner_rate(), Some(0.0));
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use egui::{Color32, FontId, Rect};
+
+use crate::board_view;
+use crate::heatmap::{self, Grid, PositionStats, PHASES};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeatmapMode {
+    Occupancy,
+    MaxTile,
+}
+
+// 位置热图：按策略统计的格子占用率和最大方块位置，叠加在游戏棋盘上显示
+pub struct HeatmapView {
+    pub enabled: bool,
+    mode: HeatmapMode,
+    // None 表示合并所有策略
+    player: Option<String>,
+    // None 表示全部阶段
+    phase: Option<usize>,
+}
+
+impl HeatmapView {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            mode: HeatmapMode::MaxTile,
+            player: None,
+            phase: None,
+        }
+    }
+
+    fn selected(&self, positions: &BTreeMap<String, PositionStats>) -> PositionStats {
+        let mut stats = PositionStats::default();
+        for (player, player_stats) in positions {
+            if self.player.as_ref().is_none_or(|selected| selected == player) {
+                stats.merge(player_stats);
+            }
+        }
+        stats
+    }
+
+    // 设置和各策略的角落统计；返回是否点击了清空
+    pub fn ui(&mut self, ui: &mut egui::Ui, positions: &BTreeMap<String, PositionStats>) -> bool {
+        ui.checkbox(&mut self.enabled, "在棋盘上显示热图");
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.mode, HeatmapMode::MaxTile, "最大方块位置");
+            ui.radio_value(&mut self.mode, HeatmapMode::Occupancy, "格子占用率");
+        });
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("策略")
+                .selected_text(self.player.as_deref().unwrap_or("全部"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.player, None, "全部");
+                    for player in positions.keys() {
+                        ui.selectable_value(&mut self.player, Some(player.clone()), player);
+                    }
+                });
+            if self.mode == HeatmapMode::MaxTile {
+                egui::ComboBox::from_label("阶段")
+                    .selected_text(self.phase.map_or("全部".to_string(), heatmap::phase_label))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.phase, None, "全部");
+                        for phase in 0..PHASES {
+                            ui.selectable_value(&mut self.phase, Some(phase), heatmap::phase_label(phase));
+                        }
+                    });
+            }
+        });
+
+        if positions.is_empty() {
+            ui.label("暂无数据，每局结束后统计");
+        }
+        egui::Grid::new("corner_rates").striped(true).show(ui, |ui| {
+            for header in ["策略", "局数", "最大方块在角落", "结束时在角落"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for (player, stats) in positions {
+                ui.label(player);
+                ui.label(stats.games.to_string());
+                ui.label(format!("{:.1}%", stats.corner_rate().unwrap_or(0.0) * 100.0));
+                ui.label(format!("{:.1}%", stats.final_corner_rate().unwrap_or(0.0) * 100.0));
+                ui.end_row();
+            }
+        });
+        ui.button("清空位置统计").clicked()
+    }
+
+    // 每个格子按比例着色，颜色深浅相对于最大的格子，并标出百分比
+    pub fn draw_overlay(&self, painter: &egui::Painter, board_rect: Rect, positions: &BTreeMap<String, PositionStats>) {
+        let stats = self.selected(positions);
+        let (grid, samples): (&Grid, u64) = match self.mode {
+            HeatmapMode::Occupancy => (&stats.occupancy, stats.samples),
+            HeatmapMode::MaxTile => stats.max_tile_grid(self.phase),
+        };
+        if samples == 0 {
+            return;
+        }
+        let peak = grid.iter().flatten().copied().max().unwrap_or(0).max(1);
+        for (row, counts) in grid.iter().enumerate() {
+            for (col, &count) in counts.iter().enumerate() {
+                let cell = board_view::cell_rect(board_rect, row, col);
+                let alpha = (40.0 + 160.0 * count as f32 / peak as f32) as u8;
+                painter.rect_filled(cell, 5.0, Color32::from_rgba_unmultiplied(220, 40, 40, alpha));
+                painter.text(
+                    cell.center_bottom() - egui::vec2(0.0, 4.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    format!("{:.1}%", count as f64 / samples as f64 * 100.0),
+                    FontId::proportional(12.0),
+                    Color32::WHITE,
+                );
+            }
+        }
+    }
+}
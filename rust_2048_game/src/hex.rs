@@ -0,0 +1,214 @@
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use crate::board::slide_line;
+use crate::game::SeededGame;
+use crate::merge::MergeRule;
+use crate::spawner::SpawnRules;
+
+// 半径为2的六边形棋盘，共19格
+pub const RADIUS: i32 = 2;
+pub const CELL_COUNT: usize = 19;
+// 最长的一行经过中心，有5格
+const MAX_LINE: usize = (2 * RADIUS + 1) as usize;
+
+// 平顶六边形的轴坐标 (q, r)：q 为列，从左到右、每列从上到下排列
+pub const CELLS: [(i32, i32); CELL_COUNT] = {
+    let mut cells = [(0, 0); CELL_COUNT];
+    let mut index = 0;
+    let mut q = -RADIUS;
+    while q <= RADIUS {
+        let mut r = -RADIUS;
+        while r <= RADIUS {
+            let s = -q - r;
+            if s >= -RADIUS && s <= RADIUS {
+                cells[index] = (q, r);
+                index += 1;
+            }
+            r += 1;
+        }
+        q += 1;
+    }
+    cells
+};
+
+pub fn cell_index(q: i32, r: i32) -> Option<usize> {
+    CELLS.iter().position(|&cell| cell == (q, r))
+}
+
+// 平顶六边形的六个移动方向，键盘上依次对应 W、E、D、S、A、Q
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::North,
+        HexDirection::NorthEast,
+        HexDirection::SouthEast,
+        HexDirection::South,
+        HexDirection::SouthWest,
+        HexDirection::NorthWest,
+    ];
+
+    // 沿这个方向移动一格时轴坐标的变化
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            HexDirection::North => (0, -1),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::SouthEast => (1, 0),
+            HexDirection::South => (0, 1),
+            HexDirection::SouthWest => (-1, 1),
+            HexDirection::NorthWest => (-1, 0),
+        }
+    }
+}
+
+fn neighbor(index: usize, (dq, dr): (i32, i32)) -> Option<usize> {
+    let (q, r) = CELLS[index];
+    cell_index(q + dq, r + dr)
+}
+
+// 六边形棋盘，合并规则与经典2048相同
+#[derive(Debug, Clone, PartialEq)]
+pub struct HexBoard {
+    cells: [u32; CELL_COUNT],
+    pub score: u32,
+}
+
+impl HexBoard {
+    pub fn new_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut board = Self::from_cells([0; CELL_COUNT]);
+        for _ in 0..SpawnRules::CLASSIC.initial_tiles {
+            board.add_random_tile(rng);
+        }
+        board
+    }
+
+    pub fn from_cells(cells: [u32; CELL_COUNT]) -> Self {
+        HexBoard { cells, score: 0 }
+    }
+
+    pub fn get(&self, index: usize) -> u32 {
+        self.cells[index]
+    }
+
+    pub fn max_tile(&self) -> u32 {
+        self.cells.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn empty_cells(&self) -> Vec<usize> {
+        (0..CELL_COUNT).filter(|&index| self.cells[index] == 0).collect()
+    }
+
+    pub fn add_random_tile<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        if let Some(&index) = self.empty_cells().choose(rng) {
+            self.cells[index] = SpawnRules::CLASSIC.pick_value(MergeRule::Classic.spawn_values(), rng);
+        }
+    }
+
+    // 只滑动合并，不生成新方块；返回棋盘是否发生变化
+    pub fn slide(&mut self, direction: HexDirection) -> bool {
+        let old_cells = self.cells;
+        let (dq, dr) = direction.offset();
+
+        // 每一行从移动方向上最靠边的格子开始，逆着方向取出
+        for start in (0..CELL_COUNT).filter(|&index| neighbor(index, (dq, dr)).is_none()) {
+            let mut positions = Vec::with_capacity(MAX_LINE);
+            let mut current = Some(start);
+            while let Some(index) = current {
+                positions.push(index);
+                current = neighbor(index, (-dq, -dr));
+            }
+
+            let mut tiles = [0; MAX_LINE];
+            for (tile, &index) in tiles.iter_mut().zip(&positions) {
+                *tile = self.cells[index];
+            }
+            let (merged, gained) = slide_line(tiles, MergeRule::Classic);
+            for (&index, value) in positions.iter().zip(merged) {
+                self.cells[index] = value;
+            }
+            self.score += gained;
+        }
+
+        old_cells != self.cells
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        if self.cells.contains(&0) {
+            return false;
+        }
+        // 三条轴上各检查一个方向即可覆盖所有相邻格
+        (0..CELL_COUNT).all(|index| {
+            [HexDirection::North, HexDirection::NorthEast, HexDirection::SouthEast]
+                .into_iter()
+                .filter_map(|direction| neighbor(index, direction.offset()))
+                .all(|other| self.cells[other] != self.cells[index])
+        })
+    }
+}
+
+// 一局六边形游戏：与 GameSession 一样由种子决定出块
+pub type HexGame = SeededGame<HexBoard>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board_with(tiles: &[((i32, i32), u32)]) -> HexBoard {
+        let mut cells = [0; CELL_COUNT];
+        for &((q, r), value) in tiles {
+            cells[cell_index(q, r).unwrap()] = value;
+        }
+        HexBoard::from_cells(cells)
+    }
+
+    #[test]
+    fn test_hex_slide() {
+        assert_eq!(CELLS.len(), CELL_COUNT);
+        assert_eq!(cell_index(0, 0), Some(9));
+
+        // 中间一列有5格，三个2向上滑动后合并一次
+        let mut board = board_with(&[((0, -1), 2), ((0, 1), 2), ((0, 2), 2), ((1, 0), 4)]);
+        assert!(board.slide(HexDirection::North));
+        assert_eq!(board.get(cell_index(0, -2).unwrap()), 4);
+        assert_eq!(board.get(cell_index(0, -1).unwrap()), 2);
+        assert_eq!(board.get(cell_index(1, -2).unwrap()), 4);
+        assert_eq!(board.score, 4);
+
+        // 斜向的一行：(-2, 2) 到 (2, -2)
+        let mut board = board_with(&[((-2, 2), 8), ((0, 0), 8)]);
+        assert!(board.slide(HexDirection::NorthEast));
+        assert_eq!(board.get(cell_index(2, -2).unwrap()), 16);
+        assert_eq!(board.max_tile(), 16);
+        assert!(!board.slide(HexDirection::NorthEast));
+    }
+
+    #[test]
+    fn test_hex_game_over() {
+        // 按 q - r 除以3的余数放置，任意两个相邻格都不相等
+        let mut cells = [0; CELL_COUNT];
+        for (index, &(q, r)) in CELLS.iter().enumerate() {
+            cells[index] = match (q - r).rem_euclid(3) {
+                0 => 2,
+                1 => 4,
+                _ => 8,
+            };
+        }
+        let board = HexBoard::from_cells(cells);
+        assert!(board.is_game_over());
+        for direction in HexDirection::ALL {
+            assert!(!board.clone().slide(direction));
+        }
+
+        cells[cell_index(0, 0).unwrap()] = cells[cell_index(0, 1).unwrap()];
+        assert!(!HexBoard::from_cells(cells).is_game_over());
+    }
+}
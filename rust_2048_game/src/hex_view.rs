@@ -0,0 +1,133 @@
+use eframe::egui;
+use egui::{Color32, FontId, Pos2, Rect, Shape, Stroke, Vec2};
+
+use crate::board_view::{text_color, tile_color};
+use crate::hex::{HexBoard, HexDirection, HexGame, CELLS, RADIUS};
+use crate::highscore_view::ShapeRecords;
+use crate::highscores::{GameRecord, PLAYER_HUMAN};
+use crate::merge::MergeRule;
+use crate::rules::Challenge;
+
+const HEX_HIGHSCORE_FILE: &str = "hex_highscores.json";
+
+// 键盘布局 Q W E / A S D 对应左上、上、右上 / 左下、下、右下
+const KEYS: [(egui::Key, HexDirection); 6] = [
+    (egui::Key::Q, HexDirection::NorthWest),
+    (egui::Key::W, HexDirection::North),
+    (egui::Key::E, HexDirection::NorthEast),
+    (egui::Key::A, HexDirection::SouthWest),
+    (egui::Key::S, HexDirection::South),
+    (egui::Key::D, HexDirection::SouthEast),
+];
+
+// 六边形模式页面：手动游戏，统计和最高分复用方形棋盘的实现
+pub struct HexView {
+    game: HexGame,
+    game_start_time: f64,
+    records: ShapeRecords,
+}
+
+impl HexView {
+    pub fn new() -> Self {
+        Self {
+            game: HexGame::new(rand::random()),
+            game_start_time: 0.0,
+            records: ShapeRecords::load(HEX_HIGHSCORE_FILE),
+        }
+    }
+
+    pub fn handle_input(&mut self, ctx: &egui::Context) {
+        if self.game.is_game_over() {
+            return;
+        }
+        let pressed = ctx.input(|i| {
+            KEYS.into_iter()
+                .find(|(key, _)| i.key_pressed(*key))
+                .map(|(_, direction)| direction)
+        });
+        if let Some(direction) = pressed {
+            self.game.apply(direction);
+        }
+    }
+
+    // 记录当前一局并开始新的一局
+    fn reset_game(&mut self, now: f64) {
+        let board = self.game.board();
+        self.records.add(GameRecord {
+            score: board.score,
+            max_tile: board.max_tile(),
+            moves: self.game.moves(),
+            duration_secs: now - self.game_start_time,
+            player: PLAYER_HUMAN.to_string(),
+            seed: self.game.seed(),
+            date: chrono::Local::now(),
+            merge: MergeRule::Classic,
+            challenge: Challenge::Endless,
+            target: MergeRule::Classic.default_target(),
+        });
+        self.game = HexGame::new(rand::random());
+        self.game_start_time = now;
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, now: f64) {
+        ui.vertical_centered(|ui| {
+            ui.heading(format!("当前分数: {}", self.game.score()));
+            ui.label(format!("步数: {}", self.game.moves()));
+            self.records.draw_summary(ui);
+            ui.label("Q/W/E/A/S/D: 左上/上/右上/左下/下/右下");
+
+            if self.game.is_game_over() {
+                ui.colored_label(Color32::RED, "游戏结束");
+            }
+            if ui.button("新游戏").clicked() {
+                self.reset_game(now);
+            }
+
+            let size = HEX_SIZE * ((2 * RADIUS + 1) as f32 * 1.5 + 0.5);
+            let height = HEX_SIZE * 3f32.sqrt() * (2 * RADIUS + 1) as f32;
+            let (rect, _) = ui.allocate_exact_size(Vec2::new(size, height), egui::Sense::hover());
+            draw_hex_board(ui.painter(), rect, self.game.board());
+
+            self.records.draw_table(ui, "六边形最高分");
+        });
+    }
+}
+
+// 每个六边形格子的外接圆半径
+const HEX_SIZE: f32 = 36.0;
+
+// 平顶六边形的六个顶点
+fn hexagon(center: Pos2, radius: f32) -> Vec<Pos2> {
+    (0..6)
+        .map(|k| {
+            let angle = std::f32::consts::FRAC_PI_3 * k as f32;
+            center + radius * Vec2::new(angle.cos(), angle.sin())
+        })
+        .collect()
+}
+
+pub fn draw_hex_board(painter: &egui::Painter, rect: Rect, board: &HexBoard) {
+    let background = Color32::from_rgb(187, 173, 160);
+    for (index, &(q, r)) in CELLS.iter().enumerate() {
+        let center = rect.center()
+            + HEX_SIZE * Vec2::new(1.5 * q as f32, 3f32.sqrt() * (r as f32 + q as f32 / 2.0));
+        painter.add(Shape::convex_polygon(hexagon(center, HEX_SIZE), background, Stroke::NONE));
+
+        let value = board.get(index);
+        painter.add(Shape::convex_polygon(
+            hexagon(center, HEX_SIZE * 0.88),
+            tile_color(MergeRule::Classic, value),
+            Stroke::NONE,
+        ));
+        if value > 0 {
+            let font_size = if value >= 1000 { 18.0 } else { 24.0 };
+            painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                value.to_string(),
+                FontId::proportional(font_size),
+                text_color(MergeRule::Classic, value),
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use eframe::egui;
+#[cfg(feature = "serde")]
+use log::error;
+
+use crate::highscores::{GameRecord, HighScoreStore};
+use crate::merge::MergeRule;
+use crate::rules::Challenge;
+use crate::stats::GameStats;
+
+const TOP_COUNT: usize = 20;
+
+pub fn draw(ui: &mut egui::Ui, store: &HighScoreStore) {
+    let lifetime = &store.lifetime;
+
+    ui.vertical_centered(|ui| {
+        ui.heading("累计统计");
+        ui.label(format!("总场次: {}", lifetime.games));
+        if let Some(average) = lifetime.average_score() {
+            ui.label(format!("平均分数: {:.1}", average));
+        }
+        if let Some(average) = lifetime.average_moves() {
+            ui.label(format!("平均步数: {:.1}", average));
+        }
+        if let Some(average) = lifetime.average_duration_secs() {
+            ui.label(format!("平均时长: {:.1}秒", average));
+        }
+        ui.label(format!("最高分数: {}", lifetime.best_score));
+        ui.label(format!("最大方块: {}", lifetime.best_tile));
+    });
+
+    ui.add_space(10.0);
+    ui.heading("最高分");
+
+    // 每种变体和挑战模式单独排行，当前选择保存在界面状态里
+    let id = egui::Id::new("highscore_table");
+    let mut selected: (MergeRule, Challenge) = ui.data_mut(|data| *data.get_temp_mut_or_default(id));
+    let tables = store.tables();
+    if tables.len() > 1 {
+        ui.horizontal_wrapped(|ui| {
+            for (merge, challenge) in tables {
+                let label = format!("{} {}", merge.label(), challenge.label());
+                ui.selectable_value(&mut selected, (merge, challenge), label);
+            }
+        });
+        ui.data_mut(|data| data.insert_temp(id, selected));
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("highscores").striped(true).show(ui, |ui| {
+            for title in ["#", "分数", "最大方块", "步数", "时长", "玩家", "种子", "日期"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+
+            for (rank, record) in store.top(selected.0, selected.1, TOP_COUNT).into_iter().enumerate() {
+                ui.label((rank + 1).to_string());
+                ui.label(record.score.to_string());
+                ui.label(record.max_tile.to_string());
+                ui.label(record.moves.to_string());
+                ui.label(format!("{:.1}秒", record.duration_secs));
+                ui.label(&record.player);
+                ui.label(record.seed.to_string());
+                ui.label(record.date.format("%Y-%m-%d %H:%M").to_string());
+                ui.end_row();
+            }
+        });
+    });
+}
+
+// 六边形和立方体模式各自的统计和最高分，保存在单独的文件里，不和方形棋盘混在一起
+pub struct ShapeRecords {
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    file: &'static str,
+    stats: GameStats,
+    highscores: HighScoreStore,
+}
+
+impl ShapeRecords {
+    pub fn load(file: &'static str) -> Self {
+        #[allow(unused_mut)]
+        let mut records = Self {
+            file,
+            stats: GameStats::default(),
+            highscores: HighScoreStore::default(),
+        };
+        #[cfg(feature = "serde")]
+        match crate::storage::load_json::<HighScoreStore>(file) {
+            Ok(store) => records.highscores = store.unwrap_or_default(),
+            Err(e) => error!("读取最高分记录 {} 失败: {}", file, e),
+        }
+        records
+    }
+
+    // 每局结束时调用一次，立即写盘
+    pub fn add(&mut self, record: GameRecord) {
+        self.stats.record(record.score);
+        self.highscores.add(record);
+        #[cfg(feature = "serde")]
+        if let Err(e) = crate::storage::save_json(self.file, &self.highscores) {
+            error!("保存最高分记录 {} 失败: {}", self.file, e);
+        }
+    }
+
+    pub fn draw_summary(&self, ui: &mut egui::Ui) {
+        ui.label(format!("总场次: {}", self.stats.total_games));
+        if let Some(average) = self.stats.average() {
+            ui.label(format!("平均分数: {:.1}", average));
+        }
+        ui.label(format!("最高分数: {}", self.stats.max_score));
+    }
+
+    pub fn draw_table(&self, ui: &mut egui::Ui, title: &str) {
+        egui::CollapsingHeader::new(title).show(ui, |ui| {
+            draw(ui, &self.highscores);
+        });
+    }
+}
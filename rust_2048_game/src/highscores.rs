@@ -0,0 +1,217 @@
+use chrono::{DateTime, Local};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::merge::MergeRule;
+use crate::rules::Challenge;
+
+#[cfg(feature = "serde")]
+pub const HIGHSCORE_FILE: &str = "highscores.json";
+// 只保留最近的若干局明细，累计统计不受影响
+const MAX_RECORDS: usize = 10_000;
+
+pub const PLAYER_HUMAN: &str = "human";
+
+// 单局结果
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRecord {
+    pub score: u32,
+    pub max_tile: u32,
+    pub moves: usize,
+    pub duration_secs: f64,
+    // 策略名称，手动游戏为 "human"
+    pub player: String,
+    pub seed: u64,
+    pub date: DateTime<Local>,
+    // 每种变体和挑战模式有单独的最高分排行，旧记录都是经典变体的无尽模式
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub merge: MergeRule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub challenge: Challenge,
+    // 这一局的获胜目标，最大方块达到它就算获胜；旧记录按经典的2048计算
+    #[cfg_attr(feature = "serde", serde(default = "default_target"))]
+    pub target: u32,
+}
+
+#[cfg(feature = "serde")]
+fn default_target() -> u32 {
+    MergeRule::Classic.default_target()
+}
+
+impl GameRecord {
+    pub fn won(&self) -> bool {
+        self.max_tile >= self.target
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LifetimeStats {
+    pub games: u64,
+    pub total_score: u64,
+    pub total_moves: u64,
+    pub total_duration_secs: f64,
+    pub best_score: u32,
+    pub best_tile: u32,
+}
+
+impl LifetimeStats {
+    pub fn record(&mut self, record: &GameRecord) {
+        self.games += 1;
+        self.total_score += record.score as u64;
+        self.total_moves += record.moves as u64;
+        self.total_duration_secs += record.duration_secs;
+        self.best_score = self.best_score.max(record.score);
+        self.best_tile = self.best_tile.max(record.max_tile);
+    }
+
+    pub fn average_score(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.total_score as f64 / self.games as f64)
+    }
+
+    pub fn average_moves(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.total_moves as f64 / self.games as f64)
+    }
+
+    pub fn average_duration_secs(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.total_duration_secs / self.games as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HighScoreStore {
+    pub records: Vec<GameRecord>,
+    pub lifetime: LifetimeStats,
+}
+
+impl HighScoreStore {
+    pub fn add(&mut self, record: GameRecord) {
+        self.lifetime.record(&record);
+        self.records.push(record);
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    // 某种变体和挑战模式下按分数从高到低排列的前n局
+    pub fn top(&self, merge: MergeRule, challenge: Challenge, n: usize) -> Vec<&GameRecord> {
+        let mut records: Vec<&GameRecord> =
+            self.records.iter().filter(|r| r.merge == merge && r.challenge == challenge).collect();
+        records.sort_by(|a, b| b.score.cmp(&a.score).then(a.date.cmp(&b.date)));
+        records.truncate(n);
+        records
+    }
+
+    // 出现过的变体和挑战模式的组合，经典变体的无尽模式总在最前面
+    pub fn tables(&self) -> Vec<(MergeRule, Challenge)> {
+        let mut tables = vec![(MergeRule::Classic, Challenge::Endless)];
+        for record in &self.records {
+            let table = (record.merge, record.challenge);
+            if !tables.contains(&table) {
+                tables.push(table);
+            }
+        }
+        tables
+    }
+
+    // 出现过的变体，经典变体总在最前面
+    pub fn variants(&self) -> Vec<MergeRule> {
+        let mut variants = vec![MergeRule::Classic];
+        for record in &self.records {
+            if !variants.contains(&record.merge) {
+                variants.push(record.merge);
+            }
+        }
+        variants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(score: u32, moves: usize) -> GameRecord {
+        GameRecord {
+            score,
+            max_tile: 256,
+            moves,
+            duration_secs: 2.0,
+            player: "random".to_string(),
+            seed: 0,
+            date: Local::now(),
+            merge: MergeRule::Classic,
+            challenge: Challenge::Endless,
+            target: 2048,
+        }
+    }
+
+    #[test]
+    fn test_top_and_lifetime_averages() {
+        let mut store = HighScoreStore::default();
+        store.add(record(100, 10));
+        store.add(record(300, 20));
+        store.add(record(201, 31));
+
+        let top: Vec<u32> = store.top(MergeRule::Classic, Challenge::Endless, 2).iter().map(|r| r.score).collect();
+        assert_eq!(top, vec![300, 201]);
+        assert_eq!(store.lifetime.games, 3);
+        assert_eq!(store.lifetime.best_score, 300);
+        // 平均分不再被整数除法截断
+        assert!((store.lifetime.average_score().unwrap() - 200.333).abs() < 0.001);
+        assert!((store.lifetime.average_moves().unwrap() - 20.333).abs() < 0.001);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_old_records_use_the_classic_target() {
+        let mut value = serde_json::to_value(record(100, 10)).unwrap();
+        value.as_object_mut().unwrap().remove("target");
+        let loaded: GameRecord = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.target, 2048);
+        assert!(!loaded.won());
+        assert!(GameRecord { target: 256, ..loaded }.won());
+    }
+
+    #[test]
+    fn test_records_are_capped_but_lifetime_is_not() {
+        let mut store = HighScoreStore::default();
+        for i in 0..MAX_RECORDS + 5 {
+            store.add(record(i as u32, 1));
+        }
+        assert_eq!(store.records.len(), MAX_RECORDS);
+        assert_eq!(store.lifetime.games, (MAX_RECORDS + 5) as u64);
+        assert_eq!(store.records[0].score, 5);
+    }
+
+    #[test]
+    fn test_variants_and_challenges_have_separate_tables() {
+        let limited = Challenge::MoveLimit { moves: 100 };
+        let mut store = HighScoreStore::default();
+        store.add(record(100, 10));
+        store.add(GameRecord {
+            challenge: limited,
+            ..record(500, 100)
+        });
+        store.add(GameRecord {
+            merge: MergeRule::Fibonacci,
+            ..record(900, 100)
+        });
+
+        assert_eq!(store.top(MergeRule::Classic, Challenge::Endless, 5).len(), 1);
+        assert_eq!(store.top(MergeRule::Classic, limited, 5)[0].score, 500);
+        assert_eq!(store.top(MergeRule::Fibonacci, Challenge::Endless, 5)[0].score, 900);
+        assert_eq!(
+            store.tables(),
+            vec![
+                (MergeRule::Classic, Challenge::Endless),
+                (MergeRule::Classic, limited),
+                (MergeRule::Fibonacci, Challenge::Endless)
+            ]
+        );
+        assert_eq!(store.variants(), vec![MergeRule::Classic, MergeRule::Fibonacci]);
+        assert_eq!(store.lifetime.games, 3);
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+use eframe::egui;
+use egui::{Color32, FontId, Pos2, Rect, Stroke, Vec2};
+
+use crate::board::{Board, Direction};
+use crate::strategy::StrategyKind;
+
+// 提示模式：在后台线程中用当前策略评估棋盘，给出推荐方向和各方向的估值
+pub struct Hint {
+    board: Option<Board>,
+    kind: Option<StrategyKind>,
+    values: Option<Vec<Option<f64>>>,
+    receiver: Option<Receiver<Vec<Option<f64>>>>,
+    cancel: Arc<AtomicBool>,
+    pub error: Option<String>,
+}
+
+impl Hint {
+    pub fn new() -> Self {
+        Self {
+            board: None,
+            kind: None,
+            values: None,
+            receiver: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            error: None,
+        }
+    }
+
+    // 棋盘或策略变化时取消旧的计算并重新开始，否则只检查结果是否已经算完
+    pub fn update(&mut self, board: &Board, kind: &StrategyKind) {
+        if self.board.as_ref() == Some(board) && self.kind.as_ref() == Some(kind) {
+            self.poll();
+            return;
+        }
+
+        self.clear();
+        self.board = Some(board.clone());
+        self.kind = Some(kind.clone());
+        if !kind.evaluates() {
+            self.error = Some("随机策略不评估局面，请选择其它策略".to_string());
+            return;
+        }
+
+        let mut strategy = match kind.build(rand::random()) {
+            Ok(strategy) => strategy,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        strategy.set_cancel_flag(self.cancel.clone());
+
+        let (sender, receiver) = mpsc::channel();
+        let board = board.clone();
+        thread::spawn(move || {
+            // 结果过期时接收端已被丢弃，发送失败可以忽略
+            let _ = sender.send(strategy.evaluate(&board));
+        });
+        self.receiver = Some(receiver);
+    }
+
+    fn poll(&mut self) {
+        if let Some(values) = self.receiver.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            self.values = Some(values);
+            self.receiver = None;
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.board = None;
+        self.kind = None;
+        self.values = None;
+        self.receiver = None;
+        self.error = None;
+    }
+
+    pub fn best(&self) -> Option<Direction> {
+        let values = self.values.as_ref()?;
+        Direction::ALL
+            .into_iter()
+            .zip(values.iter().copied())
+            .filter_map(|(direction, value)| value.map(|value| (direction, value)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(direction, _)| direction)
+    }
+
+    // 在棋盘上画出推荐方向的箭头，并在四边标出各方向的估值
+    pub fn draw(&self, painter: &egui::Painter, board_rect: Rect) {
+        let Some(values) = &self.values else {
+            if let Some(e) = &self.error {
+                painter.text(
+                    Pos2::new(board_rect.center().x, board_rect.top() - 10.0),
+                    egui::Align2::CENTER_CENTER,
+                    e,
+                    FontId::proportional(12.0),
+                    Color32::RED,
+                );
+            } else if self.is_pending() {
+                painter.text(
+                    Pos2::new(board_rect.center().x, board_rect.top() - 10.0),
+                    egui::Align2::CENTER_CENTER,
+                    "计算中...",
+                    FontId::proportional(12.0),
+                    Color32::GRAY,
+                );
+            }
+            return;
+        };
+
+        let best = self.best();
+        for (direction, &value) in Direction::ALL.into_iter().zip(values) {
+            let (pos, align) = match direction {
+                Direction::Up => (Pos2::new(board_rect.center().x, board_rect.top() - 10.0), egui::Align2::CENTER_CENTER),
+                Direction::Down => (Pos2::new(board_rect.center().x, board_rect.bottom() + 10.0), egui::Align2::CENTER_CENTER),
+                Direction::Left => (Pos2::new(board_rect.left() - 5.0, board_rect.center().y), egui::Align2::RIGHT_CENTER),
+                Direction::Right => (Pos2::new(board_rect.right() + 5.0, board_rect.center().y), egui::Align2::LEFT_CENTER),
+            };
+            let text = value.map_or("—".to_string(), |value| format!("{:.0}", value));
+            let color = if Some(direction) == best { Color32::GREEN } else { Color32::GRAY };
+            painter.text(pos, align, text, FontId::proportional(12.0), color);
+        }
+
+        if let Some(direction) = best {
+            let length = board_rect.width() * 0.3;
+            let vec = match direction {
+                Direction::Up => Vec2::new(0.0, -length),
+                Direction::Down => Vec2::new(0.0, length),
+                Direction::Left => Vec2::new(-length, 0.0),
+                Direction::Right => Vec2::new(length, 0.0),
+            };
+            let color = Color32::from_rgba_unmultiplied(40, 160, 60, 200);
+            painter.arrow(board_rect.center() - vec * 0.5, vec, Stroke::new(8.0, color));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::MctsConfig;
+
+    fn wait(hint: &mut Hint, board: &Board, kind: &StrategyKind) {
+        for _ in 0..1000 {
+            hint.update(board, kind);
+            if !hint.is_pending() {
+                return;
+            }
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("提示计算超时");
+    }
+
+    // 只看移动本身得分的蒙特卡洛策略，结果是确定的
+    fn greedy() -> StrategyKind {
+        StrategyKind::Mcts(MctsConfig {
+            rollouts: 1,
+            depth_limit: Some(0),
+            time_budget: None,
+            threads: 1,
+            guided: false,
+        })
+    }
+
+    #[test]
+    fn test_stale_results_are_discarded() {
+        let only_right = Board::from_cells([[2, 0, 0, 0], [4, 0, 0, 0], [8, 0, 0, 0], [16, 0, 0, 0]]);
+        let only_left = Board::from_cells([[0, 0, 0, 2], [0, 0, 0, 4], [0, 0, 0, 8], [0, 0, 0, 16]]);
+        let kind = greedy();
+
+        let mut hint = Hint::new();
+        hint.update(&only_right, &kind);
+        wait(&mut hint, &only_left, &kind);
+        assert_eq!(hint.best(), Some(Direction::Left));
+
+        hint.clear();
+        assert_eq!(hint.best(), None);
+    }
+
+    #[test]
+    fn test_best_of_several_moves() {
+        // 左右都能合并两个4，上下可以移动但没有得分
+        let board = Board::from_cells([[4, 4, 0, 0], [2, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]);
+        let mut hint = Hint::new();
+        wait(&mut hint, &board, &greedy());
+        let values = hint.values.clone().unwrap();
+        assert_eq!(values, [None, Some(0.0), Some(8.0), Some(8.0)]);
+        assert!(matches!(hint.best(), Some(Direction::Left | Direction::Right)));
+
+        hint.update(&board, &StrategyKind::Random);
+        assert!(!hint.is_pending());
+        assert!(hint.error.is_some());
+        assert_eq!(hint.best(), None);
+    }
+}
@@ -1,15 +1,112 @@
+use std::collections::BTreeMap;
+
 use eframe::egui;
-use egui::{Color32, FontId, Pos2, Rect, Vec2};
-use log::info;
-use rand::prelude::*;
+use egui::{Color32, Pos2};
+use log::{debug, error, info};
 
 mod board;
+mod board_view;
+mod cube;
+mod cube_view;
+mod daily;
+mod editor_view;
+mod game;
+mod grid_view;
+mod headless;
+mod heatmap;
+mod heatmap_view;
+mod hex;
+mod hex_view;
+mod highscore_view;
+mod highscores;
+mod hint;
+mod merge;
+mod ntuple;
+mod puzzle;
+mod puzzle_view;
 mod recorder;
-use board::{Board, Direction};
+mod replay;
+mod replay_view;
+mod rules;
+mod rules_view;
+mod run_control;
+mod session;
+mod spawn_view;
+mod spawner;
+mod stats;
+mod stats_view;
+mod strategy;
+mod strategy_view;
+mod tournament;
+mod train;
+mod worker;
+#[cfg(feature = "serde")]
+mod storage;
+use board::Direction;
+use highscores::{GameRecord, HighScoreStore, PLAYER_HUMAN};
+use cube_view::CubeView;
+use daily::{DailyHistory, DailyRun};
+use editor_view::{EditorAction, EditorView};
+use grid_view::GridView;
+use heatmap::PositionStats;
+use heatmap_view::HeatmapView;
+use hex_view::HexView;
+use hint::Hint;
+use puzzle_view::PuzzleView;
 use recorder::GameRecorder;
+use replay_view::ReplayView;
+use rules::{Challenge, GameRules, GameStatus};
+use run_control::RunControl;
+use session::GameSession;
+use spawner::{SpawnPolicy, SpawnRules};
+use stats::GameStats;
+use stats_view::StatsView;
+use strategy::StrategyKind;
+use worker::MoveWorker;
+#[cfg(feature = "serde")]
+use storage::{SaveGame, AUTOSAVE, SAVE_SLOTS};
+
+// 命令行子命令，参数不包含子命令本身
+type Command = fn(&[String]) -> Result<(), Box<dyn std::error::Error>>;
 
 fn main() {
     env_logger::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command: Option<Command> = match args.first().map(String::as_str) {
+        Some("headless") => Some(headless::run),
+        Some("train") => Some(train::run),
+        Some("tournament") => Some(tournament::run),
+        Some("daily") => Some(daily::run),
+        #[cfg(feature = "serde")]
+        Some("puzzle") => Some(puzzle::run),
+        _ => None,
+    };
+    if let Some(command) = command {
+        if let Err(e) = command(&args[1..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    run_gui();
+}
+
+#[derive(PartialEq)]
+enum View {
+    Game,
+    Hex,
+    Cube,
+    Puzzle,
+    Editor,
+    Grid,
+    Replay,
+    HighScores,
+    Stats,
+}
+
+fn run_gui() {
     let mut options = eframe::NativeOptions {
         initial_window_size: Some(egui::Vec2::new(500.0, 700.0)),
         resizable: true,
@@ -32,66 +129,626 @@ fn main() {
         .insert(0, "microsoft_yahei".to_owned());
     options.follow_system_theme = false;
 
-    eframe::run_native(
+    if let Err(e) = eframe::run_native(
         "2048 自动测试",
         options,
         Box::new(|cc| {
             cc.egui_ctx.set_fonts(fonts);
             Box::new(AutoTestApp::new())
         }),
-    );
+    ) {
+        error!("启动窗口失败: {}", e);
+    }
 }
 
 struct AutoTestApp {
-    board: Board,
+    view: View,
+    session: GameSession,
+    hex: HexView,
+    cube: CubeView,
+    puzzle: PuzzleView,
+    editor: EditorView,
+    grid: GridView,
+    replay: ReplayView,
     game_over: bool,
-    total_games: u32,
-    total_score: u32,
-    max_score: u32,
-    last_move_time: f64,
-    move_interval: f64,
+    stats: GameStats,
+    highscores: HighScoreStore,
+    stats_view: StatsView,
+    // 手动模式下使用方向键操作，自动移动暂停
+    manual: bool,
+    game_start_time: f64,
+    // 上一帧的时间，用于推进限时模式的计时
+    last_frame_time: f64,
+    control: RunControl,
+    strategy_kind: StrategyKind,
+    // 自动模式的策略在后台线程中运行
+    worker: MoveWorker,
+    strategy_error: Option<String>,
+    // 新一局使用的出块方式，当前局保持开局时的设置
+    spawn_policy: SpawnPolicy,
+    spawn_rules: SpawnRules,
+    rules: GameRules,
+    show_hint: bool,
+    hint: Hint,
+    // 最高分记录有变化但尚未写盘，极速模式下避免每局都写文件
+    highscores_dirty: bool,
+    last_highscore_save: f64,
     recorder: Option<GameRecorder>,
     recording_start_time: Option<f64>,
     recording_duration: f64,
+    // 当前对局是每日挑战时的日期和是否为正式成绩
+    daily: Option<DailyRun>,
+    daily_history: DailyHistory,
+    // 待验证的分享码和验证结果
+    daily_code: String,
+    daily_message: Option<String>,
+    // 当前对局从编辑器的局面开始，不计入最高分和统计
+    edited: bool,
+    // 按策略累计的位置统计，每局结束时合并当前对局的统计
+    positions: BTreeMap<String, PositionStats>,
+    // 当前对局的位置统计，每走一步记录一次
+    game_positions: PositionStats,
+    heatmap: HeatmapView,
+    // 启动时发现的自动存档，等待玩家选择是否继续
+    #[cfg(feature = "serde")]
+    pending_resume: Option<SaveGame>,
+    #[cfg(feature = "serde")]
+    status_message: Option<String>,
 }
 
 impl AutoTestApp {
     fn new() -> Self {
-        Self {
-            board: Board::new(),
+        #[allow(unused_mut)]
+        let mut app = Self {
+            view: View::Game,
+            session: GameSession::new(rand::random()),
+            hex: HexView::new(),
+            cube: CubeView::new(),
+            puzzle: PuzzleView::new(),
+            editor: EditorView::new(),
+            grid: GridView::new(),
+            replay: ReplayView::new(),
             game_over: false,
-            total_games: 0,
-            total_score: 0,
-            max_score: 0,
-            last_move_time: 0.0,
-            move_interval: 0.2,
+            stats: GameStats::default(),
+            highscores: HighScoreStore::default(),
+            stats_view: StatsView::new(),
+            manual: false,
+            game_start_time: 0.0,
+            last_frame_time: 0.0,
+            control: RunControl::new(0.2),
+            strategy_kind: StrategyKind::Random,
+            worker: MoveWorker::new(Box::new(strategy::RandomStrategy::new(rand::random()))),
+            strategy_error: None,
+            spawn_policy: SpawnPolicy::Random,
+            spawn_rules: SpawnRules::CLASSIC,
+            rules: GameRules::default(),
+            show_hint: false,
+            hint: Hint::new(),
+            highscores_dirty: false,
+            last_highscore_save: 0.0,
             recorder: None,
             recording_start_time: None,
             recording_duration: 30.0, // 录制30秒
+            daily: None,
+            daily_history: DailyHistory::default(),
+            daily_code: String::new(),
+            daily_message: None,
+            edited: false,
+            positions: BTreeMap::new(),
+            game_positions: PositionStats::default(),
+            heatmap: HeatmapView::new(),
+            #[cfg(feature = "serde")]
+            pending_resume: None,
+            #[cfg(feature = "serde")]
+            status_message: None,
+        };
+
+        #[cfg(feature = "serde")]
+        match storage::load_json::<SaveGame>(AUTOSAVE) {
+            Ok(save) => app.pending_resume = save,
+            Err(e) => error!("读取自动存档失败: {}", e),
+        }
+        #[cfg(feature = "serde")]
+        match storage::load_json::<HighScoreStore>(highscores::HIGHSCORE_FILE) {
+            Ok(store) => app.highscores = store.unwrap_or_default(),
+            Err(e) => error!("读取最高分记录失败: {}", e),
+        }
+        #[cfg(feature = "serde")]
+        match storage::load_json::<DailyHistory>(daily::DAILY_FILE) {
+            Ok(history) => app.daily_history = history.unwrap_or_default(),
+            Err(e) => error!("读取每日挑战记录失败: {}", e),
+        }
+        #[cfg(feature = "serde")]
+        match storage::load_json::<BTreeMap<String, PositionStats>>(heatmap::POSITIONS_FILE) {
+            Ok(positions) => app.positions = positions.unwrap_or_default(),
+            Err(e) => error!("读取位置统计失败: {}", e),
+        }
+        app.start_positions();
+        app
+    }
+
+    fn player_name(&self) -> &str {
+        if self.manual {
+            PLAYER_HUMAN
+        } else {
+            self.worker.name()
+        }
+    }
+
+    fn manual_move(&mut self, ctx: &egui::Context) {
+        let pressed = ctx.input(|i| {
+            [
+                (egui::Key::ArrowUp, Direction::Up),
+                (egui::Key::ArrowDown, Direction::Down),
+                (egui::Key::ArrowLeft, Direction::Left),
+                (egui::Key::ArrowRight, Direction::Right),
+            ]
+            .into_iter()
+            .find(|(key, _)| i.key_pressed(*key))
+            .map(|(_, direction)| direction)
+        });
+        if let Some(direction) = pressed {
+            self.apply_move(direction);
+            self.game_over = self.session.is_game_over();
         }
     }
 
-    fn auto_move(&mut self) {
-        let mut rng = rand::thread_rng();
-        let directions = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
-        let direction = directions.choose(&mut rng).unwrap();
-        self.board.move_tiles(*direction);
+    // 最多等待 wait，后台还没算完这一步时返回 false
+    fn auto_move(&mut self, wait: std::time::Duration) -> bool {
+        // 自动模式达到目标后直接继续，需要停下时使用运行控制里的目标方块
+        if self.session.status() == GameStatus::Won {
+            info!("达成目标 {}，继续游戏", self.session.rules().target);
+            self.session.continue_playing();
+        }
+        let Some(direction) = self.worker.next_move(self.session.board(), wait) else {
+            return false;
+        };
+        if let Some(direction) = direction {
+            self.apply_move(direction);
+        }
+        true
     }
 
-    fn reset_game(&mut self) {
-        self.total_games += 1;
-        self.total_score += self.board.score;
-        if self.board.score > self.max_score {
-            self.max_score = self.board.score;
+    fn apply_move(&mut self, direction: Direction) {
+        if self.session.apply(direction) {
+            self.game_positions.record(self.session.board(), self.session.moves());
         }
-        self.board = Board::new();
+    }
+
+    // 新的对局开始时重新累计位置统计；读取的存档在这里重放一次之前的步数
+    fn start_positions(&mut self) {
+        self.game_positions = PositionStats::default();
+        for (moves, board) in self.session.boards().iter().enumerate() {
+            self.game_positions.record(board, moves);
+        }
+    }
+
+    fn reset_game(&mut self, now: f64) {
+        let board = self.session.board();
+        if !self.edited {
+            self.stats.record(board.score);
+            self.highscores.add(GameRecord {
+                score: board.score,
+                max_tile: board.max_tile(),
+                moves: self.session.moves(),
+                duration_secs: now - self.game_start_time,
+                player: self.player_name().to_string(),
+                seed: self.session.seed(),
+                date: chrono::Local::now(),
+                merge: self.session.rules().merge,
+                challenge: self.session.rules().challenge,
+                target: self.session.rules().target,
+            });
+            self.highscores_dirty = true;
+            let player = self.player_name().to_string();
+            self.game_positions.finish_game(self.session.board());
+            self.positions.entry(player).or_default().merge(&self.game_positions);
+        }
+        let board = self.session.board();
+        debug!("最终棋盘 {}:\n{}", board.to_compact().unwrap_or_default(), board);
+        self.record_daily();
+        self.daily = None;
+        self.edited = false;
+        self.session =
+            GameSession::with_rules(rand::random(), self.rules, self.spawn_rules).with_spawn_policy(self.spawn_policy);
+        self.start_positions();
         self.game_over = false;
-        info!("游戏重置 - 总场次: {}, 平均分数: {}", 
-            self.total_games,
-            if self.total_games > 0 { self.total_score / self.total_games } else { 0 }
+        self.game_start_time = now;
+        info!("游戏重置 - 总场次: {}, 平均分数: {:.1}", 
+            self.stats.total_games,
+            self.stats.average().unwrap_or(0.0)
         );
     }
 
+    // 从编辑器的局面开始手动游戏；分析时同时打开提示，用当前策略评估各个方向
+    fn play_edited(&mut self, action: EditorAction, now: f64) {
+        let (board, analyze) = match action {
+            EditorAction::Play(board) => (board, false),
+            EditorAction::Analyze(board) => (board, true),
+        };
+        self.reset_game(now);
+        self.session = GameSession::from_board(board, rand::random())
+            .with_game_rules(self.rules)
+            .with_spawn_rules(self.spawn_rules)
+            .with_spawn_policy(self.spawn_policy);
+        self.start_positions();
+        self.edited = true;
+        self.game_over = self.session.is_game_over();
+        self.manual = true;
+        self.show_hint = analyze;
+        self.view = View::Game;
+    }
+
+    // 开始今天的每日挑战：当天第一次开始的一局是正式成绩，之后只能练习
+    fn start_daily(&mut self, now: f64) {
+        let today = chrono::Local::now().date_naive();
+        self.reset_game(now);
+        let run = self
+            .daily_history
+            .start(today)
+            .unwrap_or(DailyRun { date: today, official: false });
+        self.save_daily_history();
+        self.session = daily::daily_session(today);
+        self.start_positions();
+        self.daily = Some(run);
+        self.manual = true;
+    }
+
+    // 把正式对局的当前进度写入每日记录
+    fn record_daily(&mut self) {
+        if let Some(run) = self.daily {
+            self.daily_history.record(run, &self.session);
+            self.save_daily_history();
+        }
+    }
+
+    fn save_daily_history(&self) {
+        #[cfg(feature = "serde")]
+        if let Err(e) = storage::save_json(daily::DAILY_FILE, &self.daily_history) {
+            error!("保存每日挑战记录失败: {}", e);
+        }
+    }
+
+    fn draw_daily(&mut self, ui: &mut egui::Ui, now: f64) {
+        ui.collapsing("每日挑战", |ui| {
+            let today = chrono::Local::now().date_naive();
+            let label = if self.daily_history.get(today).is_some() { "今日挑战（练习）" } else { "开始今日挑战" };
+            if ui.button(label).clicked() {
+                self.start_daily(now);
+            }
+
+            if let Some(run) = self.daily {
+                ui.label(format!("{} 的每日挑战（{}）", run.date, if run.official { "正式" } else { "练习" }));
+                if self.game_over {
+                    let code = daily::share_code(run.date, &self.session);
+                    ui.horizontal(|ui| {
+                        ui.label("分享码:");
+                        if ui.button("复制").clicked() {
+                            ui.output_mut(|o| o.copied_text = code.clone());
+                        }
+                    });
+                    ui.add(egui::Label::new(egui::RichText::new(code).monospace()).wrap(true));
+                }
+            }
+
+            egui::Grid::new("daily_history").striped(true).show(ui, |ui| {
+                for header in ["日期", "分数", "最大方块", "步数", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for result in self.daily_history.results().take(10) {
+                    ui.label(result.date.to_string());
+                    ui.label(result.score.to_string());
+                    ui.label(result.max_tile.to_string());
+                    ui.label(if result.finished { result.moves.to_string() } else { format!("{}（未完成）", result.moves) });
+                    if ui.button("复制分享码").clicked() {
+                        ui.output_mut(|o| o.copied_text = result.code.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.daily_code);
+                if ui.button("验证").clicked() {
+                    self.daily_message = Some(match daily::verify(&self.daily_code) {
+                        Ok(verified) => format!(
+                            "验证通过: {} 分数 {}，最大方块 {}，步数 {}",
+                            verified.date, verified.score, verified.max_tile, verified.moves
+                        ),
+                        Err(e) => e,
+                    });
+                }
+            });
+            if let Some(message) = &self.daily_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    // 极速模式下一帧执行多步，但每帧最多占用这么长时间
+    const TURBO_FRAME_BUDGET: std::time::Duration = std::time::Duration::from_millis(12);
+
+    fn run_auto_moves(&mut self, now: f64) {
+        let mut due = self.control.moves_due(now);
+        // 上一帧没算完的一步在算完后照常执行
+        if self.worker.is_pending() {
+            due = due.max(1);
+        }
+        let started = std::time::Instant::now();
+        for _ in 0..due {
+            if !self.auto_move(Self::TURBO_FRAME_BUDGET.saturating_sub(started.elapsed())) {
+                break;
+            }
+            if self.control.check_target(self.session.board().max_tile()) {
+                break;
+            }
+            if self.session.is_game_over() {
+                self.reset_game(now);
+                if self.control.game_finished() {
+                    break;
+                }
+            }
+            if started.elapsed() >= Self::TURBO_FRAME_BUDGET {
+                break;
+            }
+        }
+    }
+
+    // 位置统计和最高分记录在每局结束时一起更新，也一起写盘
+    fn save_highscores(&mut self, now: f64) {
+        self.last_highscore_save = now;
+        if std::mem::take(&mut self.highscores_dirty) {
+            #[cfg(feature = "serde")]
+            if let Err(e) = storage::save_json(highscores::HIGHSCORE_FILE, &self.highscores) {
+                error!("保存最高分记录失败: {}", e);
+            }
+            self.save_positions();
+        }
+    }
+
+    fn save_positions(&self) {
+        #[cfg(feature = "serde")]
+        if let Err(e) = storage::save_json(heatmap::POSITIONS_FILE, &self.positions) {
+            error!("保存位置统计失败: {}", e);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn save_game(&self) -> SaveGame {
+        SaveGame {
+            session: self.session.clone(),
+            stats: self.stats.clone(),
+            daily: self.daily,
+            edited: self.edited,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    // 读取存档后重新开始计时，最高分记录中的用时从读取时算起
+    fn load_game(&mut self, save: SaveGame, now: f64) {
+        self.record_daily();
+        self.daily = save.daily.map(|run| self.daily_history.resume(run, &save.session));
+        self.session = save.session;
+        self.start_positions();
+        self.edited = save.edited;
+        self.spawn_policy = self.session.spawn_policy();
+        self.spawn_rules = *self.session.spawn_rules();
+        self.rules = *self.session.rules();
+        self.stats = save.stats;
+        self.game_over = self.session.is_game_over();
+        self.game_start_time = now;
+    }
+
+    #[cfg(feature = "serde")]
+    fn awaiting_resume(&self) -> bool {
+        self.pending_resume.is_some()
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn awaiting_resume(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "serde")]
+    fn draw_resume_prompt(&mut self, ctx: &egui::Context, now: f64) {
+        let Some(save) = &self.pending_resume else {
+            return;
+        };
+
+        let mut resume = None;
+        egui::Window::new("继续游戏")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "上次游戏: 分数 {}, 步数 {}",
+                    save.session.score(),
+                    save.session.moves()
+                ));
+                ui.label(format!("累计场次: {}", save.stats.total_games));
+                ui.horizontal(|ui| {
+                    if ui.button("继续").clicked() {
+                        resume = Some(true);
+                    }
+                    if ui.button("新游戏").clicked() {
+                        resume = Some(false);
+                    }
+                });
+            });
+
+        match resume {
+            Some(true) => {
+                if let Some(save) = self.pending_resume.take() {
+                    self.load_game(save, now);
+                }
+            }
+            // 开始新游戏时保留累计统计
+            Some(false) => {
+                if let Some(save) = self.pending_resume.take() {
+                    self.stats = save.stats;
+                    self.game_start_time = now;
+                }
+            }
+            None => {}
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn draw_save_slots(&mut self, ui: &mut egui::Ui, now: f64) {
+        ui.collapsing("存档", |ui| {
+            for slot in 1..=SAVE_SLOTS {
+                ui.horizontal(|ui| {
+                    ui.label(format!("槽位 {}", slot));
+                    if ui.button("保存").clicked() {
+                        self.status_message = Some(match storage::save_json(&storage::slot_file(slot), &self.save_game()) {
+                            Ok(()) => format!("已保存到槽位 {}", slot),
+                            Err(e) => format!("保存失败: {}", e),
+                        });
+                    }
+                    if ui.button("读取").clicked() {
+                        self.status_message = Some(match storage::load_json::<SaveGame>(&storage::slot_file(slot)) {
+                            Ok(Some(save)) => {
+                                self.load_game(save, now);
+                                format!("已读取槽位 {}", slot)
+                            }
+                            Ok(None) => format!("槽位 {} 为空", slot),
+                            Err(e) => format!("读取失败: {}", e),
+                        });
+                    }
+                });
+            }
+            if let Some(message) = &self.status_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    fn draw_game(&mut self, ui: &mut egui::Ui, now: f64) {
+        // 显示测试统计信息
+        ui.vertical_centered(|ui| {
+            ui.heading(format!("当前分数: {}", self.session.score()));
+            ui.label(format!("步数: {}", self.session.moves()));
+            if let Some(progress) = self.session.challenge_progress() {
+                ui.label(progress);
+            }
+            if self.edited {
+                ui.label("从编辑器的局面开始，不计入最高分");
+            }
+            if self.session.board().blocker_count() > 0 {
+                ui.label(format!("障碍: {}", self.session.board().blocker_count()));
+            }
+            if let Some(next) = self.session.board().next_tile() {
+                board_view::draw_next_tile(ui, self.session.board().merge_rule(), next);
+            }
+            ui.label(format!("总场次: {}", self.stats.total_games));
+            if let Some(average) = self.stats.average() {
+                ui.label(format!("平均分数: {:.1}", average));
+            }
+            ui.label(format!("最高分数: {}", self.stats.max_score));
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.manual, "手动模式（方向键）");
+                if self.manual {
+                    ui.checkbox(&mut self.show_hint, "显示提示");
+                }
+            });
+            // 提示使用同一个策略设置
+            if !self.manual || self.show_hint {
+                if strategy_view::draw(ui, &mut self.strategy_kind) {
+                    // 加载失败时继续使用原来的策略
+                    match self.strategy_kind.build(rand::random()) {
+                        Ok(strategy) => {
+                            self.worker.set_strategy(strategy);
+                            self.strategy_error = None;
+                        }
+                        Err(e) => self.strategy_error = Some(e),
+                    }
+                }
+                if let Some(e) = &self.strategy_error {
+                    ui.colored_label(Color32::RED, e);
+                }
+            }
+            spawn_view::draw_policy(ui, &mut self.spawn_policy);
+            egui::CollapsingHeader::new("出块规则").show(ui, |ui| {
+                spawn_view::draw_rules(ui, &mut self.spawn_rules, self.rules.merge);
+            });
+            let variant = self.rules.merge;
+            rules_view::draw(ui, &mut self.rules);
+            // 切换变体时出块权重回到该变体的默认值
+            if self.rules.merge != variant {
+                self.spawn_rules.weights = self.rules.merge.default_spawn_weights();
+            }
+            if self.spawn_policy != self.session.spawn_policy()
+                || &self.spawn_rules != self.session.spawn_rules()
+                || &self.rules != self.session.rules()
+            {
+                ui.label("出块和获胜设置在新的一局生效");
+            }
+            if !self.manual {
+                self.control.draw(ui);
+            }
+            if self.manual && self.session.status() == GameStatus::Won {
+                ui.colored_label(Color32::GREEN, format!("达成 {}！", self.session.rules().target));
+                ui.horizontal(|ui| {
+                    if ui.button("继续游戏").clicked() {
+                        self.session.continue_playing();
+                    }
+                    if ui.button("新游戏").clicked() {
+                        self.reset_game(now);
+                    }
+                });
+            }
+            if self.manual && self.game_over {
+                if self.session.challenge_over() && self.session.has_won() {
+                    ui.colored_label(
+                        Color32::GREEN,
+                        format!("用时 {:.1}秒达成 {}！", self.session.elapsed(), self.session.rules().target),
+                    );
+                }
+                ui.colored_label(
+                    Color32::RED,
+                    if self.session.challenge_over() { "挑战结束" } else { "游戏结束" },
+                );
+                if ui.button("新游戏").clicked() {
+                    self.reset_game(now);
+                }
+            }
+
+            if let Some(start_time) = self.recording_start_time {
+                let remaining_time = (self.recording_duration - (now - start_time)).max(0.0);
+                ui.label(format!("录制中... 剩余时间: {:.1}秒", remaining_time));
+            }
+
+            self.draw_daily(ui, now);
+            egui::CollapsingHeader::new("位置分析").show(ui, |ui| {
+                if self.heatmap.ui(ui, &self.positions) {
+                    self.positions.clear();
+                    self.save_positions();
+                }
+            });
+            #[cfg(feature = "serde")]
+            self.draw_save_slots(ui, now);
+        });
+
+        // 绘制游戏棋盘
+        let board_rect = board_view::board_rect(ui);
+        board_view::draw_board(ui.painter(), board_rect, self.session.board());
+        if self.heatmap.enabled {
+            self.heatmap.draw_overlay(ui.painter(), board_rect, &self.positions);
+        }
+
+        // 达成目标等待选择是否继续时不能移动，不显示提示
+        let movable = matches!(self.session.status(), GameStatus::Playing | GameStatus::Continuing);
+        if self.manual && self.show_hint && movable {
+            self.hint.update(self.session.board(), &self.strategy_kind);
+            self.hint.draw(ui.painter(), board_rect);
+        } else {
+            self.hint.clear();
+        }
+    }
+
     fn start_recording(&mut self, window_pos: Pos2) {
         self.recorder = Some(GameRecorder::new(
             window_pos.x as i32,
@@ -129,108 +786,130 @@ impl eframe::App for AutoTestApp {
             self.start_recording(frame.info().window_info.position.unwrap_or_default());
         }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // 显示测试统计信息
-            ui.vertical_centered(|ui| {
-                ui.heading(format!("当前分数: {}", self.board.score));
-                ui.label(format!("总场次: {}", self.total_games));
-                if self.total_games > 0 {
-                    ui.label(format!("平均分数: {}", self.total_score / self.total_games));
-                }
-                ui.label(format!("最高分数: {}", self.max_score));
-
-                if let Some(start_time) = self.recording_start_time {
-                    let remaining_time = (self.recording_duration - (now - start_time)).max(0.0);
-                    ui.label(format!("录制中... 剩余时间: {:.1}秒", remaining_time));
-                }
+        egui::TopBottomPanel::top("nav").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.view, View::Game, "游戏");
+                ui.selectable_value(&mut self.view, View::Hex, "六边形");
+                ui.selectable_value(&mut self.view, View::Cube, "立方体");
+                ui.selectable_value(&mut self.view, View::Puzzle, "谜题");
+                ui.selectable_value(&mut self.view, View::Editor, "编辑器");
+                ui.selectable_value(&mut self.view, View::Grid, "多棋盘");
+                ui.selectable_value(&mut self.view, View::Replay, "回放");
+                ui.selectable_value(&mut self.view, View::HighScores, "最高分");
+                ui.selectable_value(&mut self.view, View::Stats, "统计");
             });
+        });
 
-            // 绘制游戏棋盘
-            let board_size = 300.0;
-            let cell_size = board_size / 4.0;
-            let board_rect = Rect::from_min_size(
-                Pos2::new(
-                    (ui.available_width() - board_size) / 2.0,
-                    ui.cursor().min.y + 20.0,
-                ),
-                Vec2::new(board_size, board_size),
-            );
-
-            let painter = ui.painter();
-
-            // 绘制背景
-            painter.rect_filled(
-                board_rect,
-                5.0,
-                Color32::from_rgb(187, 173, 160),
-            );
-
-            // 绘制每个格子
-            for row in 0..4 {
-                for col in 0..4 {
-                    let cell_value = self.board.get_cell(row, col);
-                    let cell_rect = Rect::from_min_size(
-                        Pos2::new(
-                            board_rect.min.x + col as f32 * cell_size + 5.0,
-                            board_rect.min.y + row as f32 * cell_size + 5.0,
-                        ),
-                        Vec2::new(cell_size - 10.0, cell_size - 10.0),
-                    );
-
-                    // 绘制格子背景
-                    let cell_color = match cell_value {
-                        0 => Color32::from_rgb(205, 193, 180),
-                        2 => Color32::from_rgb(238, 228, 218),
-                        4 => Color32::from_rgb(237, 224, 200),
-                        8 => Color32::from_rgb(242, 177, 121),
-                        16 => Color32::from_rgb(245, 149, 99),
-                        32 => Color32::from_rgb(246, 124, 95),
-                        64 => Color32::from_rgb(246, 94, 59),
-                        128 => Color32::from_rgb(237, 207, 114),
-                        256 => Color32::from_rgb(237, 204, 97),
-                        512 => Color32::from_rgb(237, 200, 80),
-                        1024 => Color32::from_rgb(237, 197, 63),
-                        2048 => Color32::from_rgb(237, 194, 46),
-                        _ => Color32::from_rgb(205, 193, 180),
-                    };
-
-                    painter.rect_filled(cell_rect, 5.0, cell_color);
-
-                    // 绘制数字
-                    if cell_value > 0 {
-                        let text_color = if cell_value <= 4 {
-                            Color32::from_rgb(119, 110, 101)
-                        } else {
-                            Color32::WHITE
-                        };
-
-                        let font_size = if cell_value >= 1000 { 24.0 } else { 32.0 };
-                        painter.text(
-                            cell_rect.center(),
-                            egui::Align2::CENTER_CENTER,
-                            cell_value.to_string(),
-                            FontId::proportional(font_size),
-                            text_color,
-                        );
-                    }
+        egui::CentralPanel::default().show(ctx, |ui| match self.view {
+            View::Game => self.draw_game(ui, now),
+            View::Hex => self.hex.draw(ui, now),
+            View::Cube => self.cube.draw(ui, now),
+            View::Puzzle => self.puzzle.draw(ui),
+            View::Grid => self.grid.draw(ui, now),
+            View::Replay => self.replay.draw(ui, now, &self.session),
+            View::Editor => {
+                if let Some(action) = self.editor.draw(ui, self.rules.merge) {
+                    self.play_edited(action, now);
                 }
             }
+            View::HighScores => highscore_view::draw(ui, &self.highscores),
+            View::Stats => self.stats_view.draw(ui, &self.highscores),
         });
 
-        // 自动移动逻辑
-        if now - self.last_move_time >= self.move_interval {
-            self.auto_move();
-            self.last_move_time = now;
+        #[cfg(feature = "serde")]
+        self.draw_resume_prompt(ctx, now);
 
-            if self.board.is_game_over() {
-                self.reset_game();
+        // 自动移动逻辑，等待玩家选择是否继续存档时暂停
+        let delta = now - std::mem::replace(&mut self.last_frame_time, now);
+        let mut cube_running = false;
+        if !self.awaiting_resume() {
+            // 限时模式的时钟只在游戏页面上走，切换到其它页面时暂停
+            if self.view == View::Game {
+                self.session.tick(delta);
+            }
+            if self.manual {
+                self.game_over = self.session.is_game_over();
+            }
+            if self.view == View::Hex {
+                self.hex.handle_input(ctx);
+            }
+            if self.view == View::Cube {
+                cube_running = self.cube.update(ctx, now);
             }
+            if self.view == View::Puzzle {
+                self.puzzle.handle_input(ctx);
+            }
+            if self.view == View::Grid {
+                self.grid.update(now);
+            }
+            // 方向键只在游戏页面操作方形棋盘，其它页面有自己的按键
+            if self.manual {
+                if !self.game_over && self.view == View::Game {
+                    self.manual_move(ctx);
+                }
+            } else {
+                self.run_auto_moves(now);
+            }
+        }
+
+        if now - self.last_highscore_save >= 5.0 {
+            self.save_highscores(now);
         }
 
         // 更新录制状态
         self.update_recording(now);
 
-        // 请求持续重绘以保持动画流畅
-        ctx.request_repaint();
+        // 只在需要时重绘：运行中按移动间隔，暂停或手动模式下等待输入，录制时保持每秒10帧
+        let mut repaint = if self.manual || self.awaiting_resume() {
+            None
+        } else {
+            self.control.repaint_after(now)
+        };
+        // 提示、自动模式的下一步和谜题求解计算中需要轮询结果
+        let moving = !self.manual && !self.awaiting_resume() && self.worker.is_pending();
+        if self.hint.is_pending() || self.puzzle.is_solving() || moving {
+            let poll = std::time::Duration::from_millis(50);
+            repaint = Some(repaint.map_or(poll, |after| after.min(poll)));
+        }
+        // 限时模式需要刷新倒计时
+        let counting_down = matches!(self.session.rules().challenge, Challenge::TimeAttack { .. })
+            && self.view == View::Game
+            && !self.session.is_game_over()
+            && !self.awaiting_resume();
+        // 多棋盘页面按自己的运行控制刷新，回放播放时按播放速度刷新
+        let view_repaint = match self.view {
+            View::Grid if !self.awaiting_resume() => self.grid.repaint_after(now),
+            View::Replay => self.replay.repaint_after(now),
+            _ => None,
+        };
+        if let Some(after) = view_repaint {
+            repaint = Some(repaint.map_or(after, |current| current.min(after)));
+        }
+        if cube_running || counting_down {
+            let step = std::time::Duration::from_millis(100);
+            repaint = Some(repaint.map_or(step, |after| after.min(step)));
+        }
+        if self.recording_start_time.is_some() {
+            let frame = std::time::Duration::from_millis(100);
+            repaint = Some(repaint.map_or(frame, |after| after.min(frame)));
+        }
+        if let Some(after) = repaint {
+            ctx.request_repaint_after(after);
+        }
+    }
+
+    // 退出时自动保存当前对局和累计统计
+    #[cfg(feature = "serde")]
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_highscores(0.0);
+        self.record_daily();
+        if self.pending_resume.is_some() {
+            // 玩家还未做选择，保留原来的自动存档
+            return;
+        }
+        match storage::save_json(AUTOSAVE, &self.save_game()) {
+            Ok(()) => info!("已自动保存到 {}", storage::data_dir().join(AUTOSAVE).display()),
+            Err(e) => error!("自动保存失败: {}", e),
+        }
     }
 }
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// 斐波那契方块序列 1, 2, 3, 5, 8...，u32 范围内共46项
+const FIBONACCI: [u32; 46] = {
+    let mut values = [0; 46];
+    values[0] = 1;
+    values[1] = 2;
+    let mut i = 2;
+    while i < values.len() {
+        values[i] = values[i - 1] + values[i - 2];
+        i += 1;
+    }
+    values
+};
+
+// 两个相邻方块能否合并以及合并成什么，决定了游戏的变体
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MergeRule {
+    // 相同的数合并为两倍
+    #[default]
+    Classic,
+    // 相邻的斐波那契数合并为它们的和（1+1=2、1+2=3、2+3=5...）
+    Fibonacci,
+    // 类似 Threes：1+2=3，3以上相同的数合并为两倍；每次只滑动一格，有下一个方块预告，只在边缘出块
+    Threes,
+}
+
+impl MergeRule {
+    pub const ALL: [MergeRule; 3] = [MergeRule::Classic, MergeRule::Fibonacci, MergeRule::Threes];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergeRule::Classic => "经典",
+            MergeRule::Fibonacci => "斐波那契",
+            MergeRule::Threes => "Threes",
+        }
+    }
+
+    // 每次滑动方块最多移动一格
+    pub fn one_step(self) -> bool {
+        self == MergeRule::Threes
+    }
+
+    // 预先抽出下一个方块并显示给玩家，新方块只出现在边缘
+    pub fn has_preview(self) -> bool {
+        self == MergeRule::Threes
+    }
+
+    // a 滑向 b 时合并得到的新方块，不能合并时为 None
+    pub fn merge(self, a: u32, b: u32) -> Option<u32> {
+        if a == 0 || b == 0 {
+            return None;
+        }
+        match self {
+            MergeRule::Classic => (a == b).then(|| a * 2),
+            // 两个不相邻的斐波那契数之和一定落在两个斐波那契数之间，所以只需检查和
+            MergeRule::Fibonacci => {
+                let sum = a.checked_add(b)?;
+                (fibonacci_index(a).is_some() && fibonacci_index(b).is_some() && fibonacci_index(sum).is_some())
+                    .then_some(sum)
+            }
+            MergeRule::Threes => match (a, b) {
+                (1, 2) | (2, 1) => Some(3),
+                _ if a == b && a >= 3 => a.checked_mul(2),
+                _ => None,
+            },
+        }
+    }
+
+    // 新方块可以取的值，与 SpawnRules 的权重一一对应
+    pub fn spawn_values(self) -> [u32; 4] {
+        match self {
+            MergeRule::Classic => [2, 4, 8, 16],
+            MergeRule::Fibonacci => [1, 2, 3, 5],
+            MergeRule::Threes => [1, 2, 3, 6],
+        }
+    }
+
+    // 切换变体时使用的默认出块权重
+    pub fn default_spawn_weights(self) -> [u32; 4] {
+        match self {
+            MergeRule::Classic | MergeRule::Fibonacci => [9, 1, 0, 0],
+            // 1和2必须配对才能合并，两者的数量要大致相当
+            MergeRule::Threes => [4, 4, 2, 0],
+        }
+    }
+
+    pub fn is_valid_tile(self, value: u32) -> bool {
+        match self {
+            MergeRule::Classic => value >= 2 && value.is_power_of_two(),
+            MergeRule::Fibonacci => fibonacci_index(value).is_some(),
+            MergeRule::Threes => value == 1 || value == 2 || (value.is_multiple_of(3) && (value / 3).is_power_of_two()),
+        }
+    }
+
+    // 方块在序列中的序号（从1开始，空格为0），用于配色和AI估值
+    pub fn rank(self, value: u32) -> u32 {
+        match self {
+            _ if value == 0 => 0,
+            MergeRule::Classic => value.trailing_zeros(),
+            MergeRule::Fibonacci => fibonacci_index(value).map_or(0, |index| index as u32 + 1),
+            // 1、2、3、6、12...依次为1、2、3、4、5...
+            MergeRule::Threes if value < 3 => value,
+            MergeRule::Threes => (value / 3).trailing_zeros() + 3,
+        }
+    }
+
+    // 序列中的下一个方块
+    pub fn next_tile(self, value: u32) -> Option<u32> {
+        match self {
+            MergeRule::Classic => value.checked_mul(2),
+            MergeRule::Fibonacci => fibonacci_index(value).and_then(|index| FIBONACCI.get(index + 1).copied()),
+            MergeRule::Threes if value < 3 => Some(3),
+            MergeRule::Threes => value.checked_mul(2),
+        }
+    }
+
+    // 与经典2048难度相当的默认目标，以及界面中的快捷选项
+    pub fn default_target(self) -> u32 {
+        match self {
+            MergeRule::Classic => 2048,
+            MergeRule::Fibonacci => 2584,
+            MergeRule::Threes => 768,
+        }
+    }
+
+    pub fn target_presets(self) -> [u32; 3] {
+        match self {
+            MergeRule::Classic => [512, 2048, 65_536],
+            MergeRule::Fibonacci => [233, 2584, 46_368],
+            MergeRule::Threes => [96, 768, 6144],
+        }
+    }
+}
+
+fn fibonacci_index(value: u32) -> Option<usize> {
+    FIBONACCI.binary_search(&value).ok()
+}
+
+impl fmt::Display for MergeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MergeRule::Classic => "classic",
+            MergeRule::Fibonacci => "fibonacci",
+            MergeRule::Threes => "threes",
+        })
+    }
+}
+
+impl FromStr for MergeRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(MergeRule::Classic),
+            "fibonacci" | "fib" => Ok(MergeRule::Fibonacci),
+            "threes" => Ok(MergeRule::Threes),
+            other => Err(format!("未知变体: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fibonacci_merges() {
+        let rule = MergeRule::Fibonacci;
+        assert_eq!(rule.merge(1, 1), Some(2));
+        assert_eq!(rule.merge(1, 2), Some(3));
+        assert_eq!(rule.merge(3, 2), Some(5));
+        assert_eq!(rule.merge(5, 8), Some(13));
+        assert_eq!(rule.merge(2, 2), None);
+        assert_eq!(rule.merge(1, 3), None);
+        assert_eq!(rule.merge(3, 8), None);
+        assert_eq!(rule.merge(0, 1), None);
+
+        assert_eq!(rule.rank(1), 1);
+        assert_eq!(rule.rank(13), 6);
+        assert_eq!(rule.next_tile(13), Some(21));
+        assert!(!rule.is_valid_tile(4));
+    }
+
+    #[test]
+    fn test_classic_merges() {
+        let rule = MergeRule::Classic;
+        assert_eq!(rule.merge(4, 4), Some(8));
+        assert_eq!(rule.merge(2, 4), None);
+        assert_eq!(rule.rank(2048), 11);
+        assert!(!rule.is_valid_tile(1));
+    }
+
+    #[test]
+    fn test_threes_merges() {
+        let rule = MergeRule::Threes;
+        assert_eq!(rule.merge(1, 2), Some(3));
+        assert_eq!(rule.merge(2, 1), Some(3));
+        assert_eq!(rule.merge(1, 1), None);
+        assert_eq!(rule.merge(2, 2), None);
+        assert_eq!(rule.merge(3, 3), Some(6));
+        assert_eq!(rule.merge(12, 12), Some(24));
+        assert_eq!(rule.merge(3, 6), None);
+
+        assert_eq!(rule.rank(6), 4);
+        assert_eq!(rule.rank(768), 11);
+        assert!(rule.is_valid_tile(96));
+        assert!(!rule.is_valid_tile(9));
+    }
+}
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use rand::Rng;
+
+use crate::board::{Board, Direction};
+use crate::strategy::{afterstates, Strategy};
+
+const MAGIC: &[u8; 4] = b"NTUP";
+// TC 累计量单独保存在权重文件旁边，使用网络时不需要读取
+const COHERENCE_MAGIC: &[u8; 4] = b"NTTC";
+const VERSION: u32 = 1;
+// 加载时允许的最长元组，与大网络相同；更长的元组权重表过大，视为文件损坏
+const MAX_TUPLE_LEN: usize = 6;
+// 每个格子用4位表示方块在序列中的序号，超过15的按15处理（经典规则下即32768）
+const MAX_EXPONENT: u8 = 15;
+
+pub const DEFAULT_WEIGHTS_FILE: &str = "ntuple_weights.bin";
+
+// 默认网络：两条直线与两个方块的4-元组，权重表较小，训练和加载都很快
+pub const SMALL_PATTERNS: [&[usize]; 4] = [&[0, 1, 2, 3], &[4, 5, 6, 7], &[0, 1, 4, 5], &[1, 2, 5, 6]];
+// 大网络：常用的四个6-元组，每个权重表 16^6 项
+pub const LARGE_PATTERNS: [&[usize]; 4] = [
+    &[0, 1, 2, 3, 4, 5],
+    &[4, 5, 6, 7, 8, 9],
+    &[0, 1, 2, 4, 5, 6],
+    &[4, 5, 6, 8, 9, 10],
+];
+
+// 第 sym 种对称变换后格子的位置（4种旋转 × 是否镜像）
+fn transform(cell: usize, sym: usize) -> usize {
+    let (mut row, mut col) = (cell / 4, cell % 4);
+    if sym >= 4 {
+        col = 3 - col;
+    }
+    for _ in 0..sym % 4 {
+        (row, col) = (col, 3 - row);
+    }
+    row * 4 + col
+}
+
+fn exponents(board: &Board) -> [u8; 16] {
+    let mut exponents = [0; 16];
+    for (cell, exponent) in exponents.iter_mut().enumerate() {
+        let rank = board.merge_rule().rank(board.get_cell(cell / 4, cell % 4));
+        *exponent = (rank as u8).min(MAX_EXPONENT);
+    }
+    exponents
+}
+
+// n-tuple 网络价值函数：所有元组（含8种对称变换）查表求和
+pub struct NTupleNetwork {
+    patterns: Vec<Vec<usize>>,
+    // 每个元组在8种对称变换下对应的格子
+    symmetric: Vec<[Vec<usize>; 8]>,
+    weights: Vec<Vec<f32>>,
+    // 时间一致性（TC）学习的累计量，只在训练时使用
+    coherence: Option<Vec<Vec<(f32, f32)>>>,
+}
+
+impl NTupleNetwork {
+    pub fn new(patterns: &[&[usize]]) -> Self {
+        let patterns: Vec<Vec<usize>> = patterns.iter().map(|p| p.to_vec()).collect();
+        let weights = patterns.iter().map(|p| vec![0.0; 1 << (4 * p.len())]).collect();
+        Self::with_weights(patterns, weights)
+    }
+
+    fn with_weights(patterns: Vec<Vec<usize>>, weights: Vec<Vec<f32>>) -> Self {
+        let symmetric = patterns
+            .iter()
+            .map(|pattern| std::array::from_fn(|sym| pattern.iter().map(|&cell| transform(cell, sym)).collect()))
+            .collect();
+        Self {
+            patterns,
+            symmetric,
+            weights,
+            coherence: None,
+        }
+    }
+
+    pub fn enable_coherence(&mut self) {
+        self.coherence = Some(self.weights.iter().map(|table| vec![(0.0, 0.0); table.len()]).collect());
+    }
+
+    pub fn has_patterns(&self, patterns: &[&[usize]]) -> bool {
+        self.patterns.len() == patterns.len() && self.patterns.iter().zip(patterns).all(|(a, b)| a == b)
+    }
+
+    fn feature_count(&self) -> usize {
+        self.patterns.len() * 8
+    }
+
+    fn indices(&self, board: &Board) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let exponents = exponents(board);
+        self.symmetric.iter().enumerate().flat_map(move |(pattern, variants)| {
+            variants.iter().map(move |cells| {
+                let index = cells
+                    .iter()
+                    .enumerate()
+                    .fold(0, |index, (k, &cell)| index | (exponents[cell] as usize) << (4 * k));
+                (pattern, index)
+            })
+        })
+    }
+
+    pub fn value(&self, board: &Board) -> f32 {
+        self.indices(board).map(|(pattern, index)| self.weights[pattern][index]).sum()
+    }
+
+    // 把误差平均分配到所有特征上
+    fn update(&mut self, board: &Board, delta: f32, alpha: f32) {
+        let step = alpha * delta / self.feature_count() as f32;
+        let indices: Vec<(usize, usize)> = self.indices(board).collect();
+        for (pattern, index) in indices {
+            let rate = match &mut self.coherence {
+                Some(coherence) => {
+                    let (error, absolute) = &mut coherence[pattern][index];
+                    let rate = if *absolute == 0.0 { 1.0 } else { error.abs() / *absolute };
+                    *error += delta;
+                    *absolute += delta.abs();
+                    rate
+                }
+                None => 1.0,
+            };
+            self.weights[pattern][index] += rate * step;
+        }
+    }
+
+    // 选出奖励加移动后局面估值最大的方向
+    pub fn best_move(&self, board: &Board) -> Option<(Direction, f32, Board)> {
+        afterstates(board)
+            .into_iter()
+            .map(|(_, direction, after)| {
+                let reward = (after.score - board.score) as f32;
+                (direction, reward + self.value(&after), after)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.patterns.len() as u32).to_le_bytes())?;
+        for pattern in &self.patterns {
+            writer.write_all(&(pattern.len() as u32).to_le_bytes())?;
+            for &cell in pattern {
+                writer.write_all(&[cell as u8])?;
+            }
+        }
+        for table in &self.weights {
+            for weight in table {
+                writer.write_all(&weight.to_le_bytes())?;
+            }
+        }
+        writer.flush()
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_u32(&mut reader)? != VERSION {
+            return Err(invalid("不是n-tuple权重文件"));
+        }
+
+        let count = read_u32(&mut reader)? as usize;
+        let mut patterns = Vec::new();
+        for _ in 0..count {
+            let len = read_u32(&mut reader)? as usize;
+            if len == 0 || len > MAX_TUPLE_LEN {
+                return Err(invalid("元组长度无效"));
+            }
+            let mut cells = vec![0; len];
+            reader.read_exact(&mut cells)?;
+            if cells.iter().any(|&cell| cell >= 16) {
+                return Err(invalid("元组格子编号无效"));
+            }
+            patterns.push(cells.into_iter().map(usize::from).collect::<Vec<_>>());
+        }
+
+        // 分配权重表之前先确认文件中剩余的字节数与元组描述一致
+        let expected: u64 = patterns.iter().map(|pattern| 4u64 << (4 * pattern.len())).sum();
+        if file_len.saturating_sub(reader.stream_position()?) != expected {
+            return Err(invalid("权重文件长度与元组描述不符"));
+        }
+
+        let mut weights = Vec::with_capacity(patterns.len());
+        for pattern in &patterns {
+            let mut bytes = vec![0; 4 << (4 * pattern.len())];
+            reader.read_exact(&mut bytes)?;
+            weights.push(
+                bytes
+                    .chunks_exact(4)
+                    .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                    .collect(),
+            );
+        }
+        Ok(Self::with_weights(patterns, weights))
+    }
+
+    // 权重文件对应的 TC 累计量文件
+    pub fn coherence_path(path: &str) -> String {
+        format!("{}.tc", path)
+    }
+
+    // 没有启用 TC 学习时不写文件
+    pub fn save_coherence(&self, path: &str) -> io::Result<()> {
+        let Some(coherence) = &self.coherence else {
+            return Ok(());
+        };
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(COHERENCE_MAGIC)?;
+        for table in coherence {
+            for (error, absolute) in table {
+                writer.write_all(&error.to_le_bytes())?;
+                writer.write_all(&absolute.to_le_bytes())?;
+            }
+        }
+        writer.flush()
+    }
+
+    // 读取之前保存的 TC 累计量，文件大小必须与当前网络的权重表一致
+    pub fn load_coherence(&mut self, path: &str) -> io::Result<()> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let file = File::open(path)?;
+        let expected: u64 = self.weights.iter().map(|table| 8 * table.len() as u64).sum();
+        if file.metadata()?.len() != COHERENCE_MAGIC.len() as u64 + expected {
+            return Err(invalid("TC累计量文件与权重文件不符"));
+        }
+        let mut reader = BufReader::new(file);
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != COHERENCE_MAGIC {
+            return Err(invalid("不是TC累计量文件"));
+        }
+        let mut coherence = Vec::with_capacity(self.weights.len());
+        for table in &self.weights {
+            let mut bytes = vec![0; 8 * table.len()];
+            reader.read_exact(&mut bytes)?;
+            coherence.push(
+                bytes
+                    .chunks_exact(8)
+                    .map(|chunk| {
+                        let error = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                        let absolute = f32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+                        (error, absolute)
+                    })
+                    .collect(),
+            );
+        }
+        self.coherence = Some(coherence);
+        Ok(())
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+// 权重文件较大，同一个文件只加载一次；训练写入新的权重后修改时间变化，重新加载
+pub fn load_cached(path: &str) -> io::Result<Arc<NTupleNetwork>> {
+    type Cache = HashMap<String, (SystemTime, Arc<NTupleNetwork>)>;
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    let modified = fs::metadata(path)?.modified()?;
+    let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+    if let Some((cached, network)) = cache.get(path) {
+        if *cached == modified {
+            return Ok(network.clone());
+        }
+    }
+    let network = Arc::new(NTupleNetwork::load(path)?);
+    cache.insert(path.to_string(), (modified, network.clone()));
+    Ok(network)
+}
+
+// 自我对弈一局并做 TD(0) 移动后局面学习，返回 (分数, 最大方块)
+pub fn train_episode<R: Rng + ?Sized>(network: &mut NTupleNetwork, alpha: f32, rng: &mut R) -> (u32, u32) {
+    let mut board = Board::new_with_rng(rng);
+    let mut previous: Option<Board> = None;
+
+    loop {
+        match network.best_move(&board) {
+            Some((_, value, after)) => {
+                if let Some(previous) = &previous {
+                    let delta = value - network.value(previous);
+                    network.update(previous, delta, alpha);
+                }
+                board = after.clone();
+                board.add_random_tile(rng);
+                previous = Some(after);
+            }
+            None => {
+                // 终局之后的价值为0
+                if let Some(previous) = &previous {
+                    let delta = -network.value(previous);
+                    network.update(previous, delta, alpha);
+                }
+                return (board.score, board.max_tile());
+            }
+        }
+    }
+}
+
+pub struct NTupleStrategy {
+    network: Arc<NTupleNetwork>,
+}
+
+impl NTupleStrategy {
+    pub fn new(network: Arc<NTupleNetwork>) -> Self {
+        Self { network }
+    }
+}
+
+impl Strategy for NTupleStrategy {
+    fn name(&self) -> &str {
+        "ntuple"
+    }
+
+    fn evaluate(&mut self, board: &Board) -> Vec<Option<f64>> {
+        let mut values = vec![None; Direction::ALL.len()];
+        for (index, _, after) in afterstates(board) {
+            let reward = (after.score - board.score) as f64;
+            values[index] = Some(reward + self.network.value(&after) as f64);
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_symmetric_boards_have_equal_value() {
+        let mut network = NTupleNetwork::new(&SMALL_PATTERNS);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            train_episode(&mut network, 0.1, &mut rng);
+        }
+
+        let board = Board::from_cells([
+            [2, 4, 8, 0],
+            [0, 16, 0, 0],
+            [0, 0, 0, 0],
+            [32, 0, 0, 0],
+        ]);
+        // 左右镜像
+        let mirrored = Board::from_cells([
+            [0, 8, 4, 2],
+            [0, 0, 16, 0],
+            [0, 0, 0, 0],
+            [0, 0, 0, 32],
+        ]);
+        assert!((network.value(&board) - network.value(&mirrored)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_transform_is_a_permutation() {
+        for sym in 0..8 {
+            let mut cells: Vec<usize> = (0..16).map(|cell| transform(cell, sym)).collect();
+            cells.sort_unstable();
+            assert_eq!(cells, (0..16).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut network = NTupleNetwork::new(&SMALL_PATTERNS);
+        network.enable_coherence();
+        train_episode(&mut network, 0.1, &mut StdRng::seed_from_u64(2));
+
+        let path = std::env::temp_dir().join(format!("ntuple_test_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        network.save(path).unwrap();
+        let loaded = NTupleNetwork::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let board = Board::from_cells([[2, 2, 4, 0], [0, 8, 0, 0], [0, 0, 0, 0], [0, 0, 0, 2]]);
+        assert_eq!(loaded.patterns, network.patterns);
+        assert_eq!(loaded.value(&board), network.value(&board));
+    }
+
+    #[test]
+    fn test_coherence_is_saved_separately() {
+        let mut network = NTupleNetwork::new(&SMALL_PATTERNS);
+        network.enable_coherence();
+        train_episode(&mut network, 0.1, &mut StdRng::seed_from_u64(3));
+
+        let path = std::env::temp_dir().join(format!("ntuple_tc_{}.bin", std::process::id()));
+        let path = NTupleNetwork::coherence_path(path.to_str().unwrap());
+        network.save_coherence(&path).unwrap();
+        let mut loaded = NTupleNetwork::new(&SMALL_PATTERNS);
+        loaded.load_coherence(&path).unwrap();
+        assert_eq!(loaded.coherence, network.coherence);
+
+        // 元组不同的网络不能使用这些累计量
+        let mut large = NTupleNetwork::new(&[&[0, 1, 2]]);
+        assert!(large.load_coherence(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.has_patterns(&SMALL_PATTERNS) && !loaded.has_patterns(&LARGE_PATTERNS));
+    }
+
+    #[test]
+    fn test_cache_reloads_changed_files() {
+        let path = std::env::temp_dir().join(format!("ntuple_cache_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        let board = Board::from_cells([[2, 2, 4, 0], [0, 8, 0, 0], [0, 0, 0, 0], [0, 0, 0, 2]]);
+        NTupleNetwork::new(&SMALL_PATTERNS).save(path).unwrap();
+        assert_eq!(load_cached(path).unwrap().value(&board), 0.0);
+
+        let mut network = NTupleNetwork::new(&SMALL_PATTERNS);
+        train_episode(&mut network, 0.1, &mut StdRng::seed_from_u64(4));
+        network.save(path).unwrap();
+        // 保证修改时间与上一次不同
+        let later = SystemTime::now() + std::time::Duration::from_secs(10);
+        File::options().write(true).open(path).unwrap().set_modified(later).unwrap();
+        assert_eq!(load_cached(path).unwrap().value(&board), network.value(&board));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_bad_headers() {
+        let path = std::env::temp_dir().join(format!("ntuple_bad_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        let header = |len: u32| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend(VERSION.to_le_bytes());
+            bytes.extend(1u32.to_le_bytes());
+            bytes.extend(len.to_le_bytes());
+            bytes.extend((0..len as u8).collect::<Vec<_>>());
+            bytes
+        };
+
+        // 8-元组需要 16GiB 的权重表，不能在检查之前分配
+        std::fs::write(path, header(8)).unwrap();
+        assert!(NTupleNetwork::load(path).is_err());
+
+        // 权重表被截断
+        let mut truncated = header(4);
+        truncated.extend(vec![0; (4 << 16) - 4]);
+        std::fs::write(path, &truncated).unwrap();
+        assert!(NTupleNetwork::load(path).is_err());
+
+        truncated.extend([0; 4]);
+        std::fs::write(path, &truncated).unwrap();
+        assert!(NTupleNetwork::load(path).is_ok());
+        std::fs::remove_file(path).unwrap();
+    }
+}
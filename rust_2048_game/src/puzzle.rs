@@ -0,0 +1,418 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Direction};
+use crate::spawner::SpawnRules;
+
+#[cfg(feature = "serde")]
+pub const PROGRESS_FILE: &str = "puzzle_progress.json";
+// 求解器默认最多展开的局面数
+pub const DEFAULT_SOLVE_LIMIT: usize = 2_000_000;
+
+// 谜题目标，文本格式为 tile:N、tiles:N 或 score:N
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    // 合成数值至少为 N 的方块
+    Tile(u32),
+    // 棋盘上最多剩下 N 个方块
+    ClearTo(usize),
+    // 分数达到 N
+    Score(u32),
+}
+
+impl Goal {
+    pub fn is_met(self, board: &Board) -> bool {
+        match self {
+            Goal::Tile(tile) => board.has_reached(tile),
+            Goal::ClearTo(count) => board.tile_count() <= count,
+            Goal::Score(score) => board.score >= score,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Goal::Tile(tile) => format!("合成 {}", tile),
+            Goal::ClearTo(count) => format!("只剩不超过 {} 个方块", count),
+            Goal::Score(score) => format!("分数达到 {}", score),
+        }
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Goal::Tile(tile) => write!(f, "tile:{}", tile),
+            Goal::ClearTo(count) => write!(f, "tiles:{}", count),
+            Goal::Score(score) => write!(f, "score:{}", score),
+        }
+    }
+}
+
+impl FromStr for Goal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("无效谜题目标: {}（应为 tile:N、tiles:N 或 score:N）", s);
+        let (kind, value) = s.split_once(':').ok_or_else(invalid)?;
+        match kind {
+            "tile" => value.parse().map(Goal::Tile).map_err(|_| invalid()),
+            "tiles" => value.parse().map(Goal::ClearTo).map_err(|_| invalid()),
+            "score" => value.parse().map(Goal::Score).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// 一道谜题：固定的起始棋盘、确定的出块序列、目标和步数上限
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "PuzzleRecord", try_from = "PuzzleRecord")
+)]
+pub struct Puzzle {
+    pub name: String,
+    pub start: Board,
+    // 没有种子时移动后不生成新方块
+    pub seed: Option<u64>,
+    pub goal: Goal,
+    pub max_moves: usize,
+}
+
+impl Puzzle {
+    // 第 moves 步之后的新方块只由种子、步数和棋盘决定，
+    // 同一局面无论经过哪条路线到达，后续出块都相同，求解器可以据此合并重复局面
+    pub fn step(&self, board: &Board, direction: Direction, moves: usize) -> Option<Board> {
+        let mut board = board.clone();
+        if !board.slide(direction) {
+            return None;
+        }
+        if let Some(seed) = self.seed {
+            let mut rng = StdRng::seed_from_u64(seed ^ (moves as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            board.add_tile_with_rules(&SpawnRules::CLASSIC, &mut rng);
+        }
+        Some(board)
+    }
+}
+
+// 谜题文件中的格式：棋盘用文本或紧凑格式，目标用字符串
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct PuzzleRecord {
+    name: String,
+    board: String,
+    #[serde(default)]
+    seed: Option<u64>,
+    goal: String,
+    max_moves: usize,
+}
+
+#[cfg(feature = "serde")]
+impl From<Puzzle> for PuzzleRecord {
+    fn from(puzzle: Puzzle) -> Self {
+        Self {
+            name: puzzle.name,
+            board: puzzle.start.to_string(),
+            seed: puzzle.seed,
+            goal: puzzle.goal.to_string(),
+            max_moves: puzzle.max_moves,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PuzzleRecord> for Puzzle {
+    type Error = String;
+
+    fn try_from(record: PuzzleRecord) -> Result<Self, Self::Error> {
+        let start = record
+            .board
+            .parse()
+            .map_err(|e| format!("谜题 {} 的棋盘无效: {}", record.name, e))?;
+        if record.max_moves == 0 {
+            return Err(format!("谜题 {} 的步数上限必须大于0", record.name));
+        }
+        Ok(Puzzle {
+            goal: record.goal.parse()?,
+            name: record.name,
+            start,
+            seed: record.seed,
+            max_moves: record.max_moves,
+        })
+    }
+}
+
+// 谜题包：一个 JSON 文件，包含名称和若干谜题
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PuzzlePack {
+    pub name: String,
+    pub puzzles: Vec<Puzzle>,
+}
+
+#[cfg(feature = "serde")]
+impl PuzzlePack {
+    pub fn builtin() -> Self {
+        serde_json::from_str(include_str!("../puzzles/basic.json")).expect("内置谜题包格式错误")
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+}
+
+// 已解开的谜题及最少步数，键为 "谜题包/谜题"
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PuzzleProgress {
+    solved: BTreeMap<String, usize>,
+}
+
+impl PuzzleProgress {
+    pub fn key(pack: &PuzzlePack, puzzle: &Puzzle) -> String {
+        format!("{}/{}", pack.name, puzzle.name)
+    }
+
+    pub fn best(&self, key: &str) -> Option<usize> {
+        self.solved.get(key).copied()
+    }
+
+    // 返回是否刷新了最少步数
+    pub fn record(&mut self, key: String, moves: usize) -> bool {
+        match self.solved.get(&key) {
+            Some(&best) if best <= moves => false,
+            _ => {
+                self.solved.insert(key, moves);
+                true
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleStatus {
+    Playing,
+    Solved,
+    // 步数用完或无路可走
+    Failed,
+}
+
+// 正在进行的一次谜题尝试
+#[derive(Debug, Clone)]
+pub struct PuzzleGame {
+    puzzle: Puzzle,
+    board: Board,
+    history: Vec<Direction>,
+}
+
+impl PuzzleGame {
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self {
+            board: puzzle.start.clone(),
+            puzzle,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn history(&self) -> &[Direction] {
+        &self.history
+    }
+
+    pub fn moves_left(&self) -> usize {
+        self.puzzle.max_moves.saturating_sub(self.history.len())
+    }
+
+    pub fn status(&self) -> PuzzleStatus {
+        if self.puzzle.goal.is_met(&self.board) {
+            PuzzleStatus::Solved
+        } else if self.moves_left() == 0 || self.board.is_game_over() {
+            PuzzleStatus::Failed
+        } else {
+            PuzzleStatus::Playing
+        }
+    }
+
+    pub fn apply(&mut self, direction: Direction) -> bool {
+        if self.status() != PuzzleStatus::Playing {
+            return false;
+        }
+        match self.puzzle.step(&self.board, direction, self.history.len()) {
+            Some(board) => {
+                self.board = board;
+                self.history.push(direction);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    // 步数最少的一种解法
+    Solved(Vec<Direction>),
+    // 已搜索完步数上限内的所有走法，确定无解
+    Unsolvable,
+    // 局面数超过上限或被取消，没有得出结论
+    LimitReached,
+}
+
+// 按步数逐层穷举所有走法，同一层中相同的局面只保留一个；
+// 找到的第一个解就是最短解，搜索完所有层仍未达成目标即证明无解；
+// 在后台求解时，cancel 被设置后尽快结束
+pub fn solve(puzzle: &Puzzle, limit: usize, cancel: &AtomicBool) -> Solution {
+    if puzzle.goal.is_met(&puzzle.start) {
+        return Solution::Solved(Vec::new());
+    }
+
+    // 每个局面记录父局面的下标和到达它的方向，用于还原解法
+    let mut nodes: Vec<(Board, usize, Direction)> = vec![(puzzle.start.clone(), 0, Direction::Up)];
+    let mut layer = vec![0];
+    for moves in 0..puzzle.max_moves {
+        let mut seen = HashSet::new();
+        let mut next_layer = Vec::new();
+        for &parent in &layer {
+            if cancel.load(Ordering::Relaxed) {
+                return Solution::LimitReached;
+            }
+            for direction in Direction::ALL {
+                let Some(board) = puzzle.step(&nodes[parent].0, direction, moves) else {
+                    continue;
+                };
+                if !seen.insert((board.to_cells(), board.score)) {
+                    continue;
+                }
+                let solved = puzzle.goal.is_met(&board);
+                nodes.push((board, parent, direction));
+                if solved {
+                    return Solution::Solved(trace(&nodes, nodes.len() - 1));
+                }
+                next_layer.push(nodes.len() - 1);
+                if nodes.len() > limit {
+                    return Solution::LimitReached;
+                }
+            }
+        }
+        if next_layer.is_empty() {
+            break;
+        }
+        layer = next_layer;
+    }
+    Solution::Unsolvable
+}
+
+fn trace(nodes: &[(Board, usize, Direction)], mut index: usize) -> Vec<Direction> {
+    let mut path = Vec::new();
+    while index != 0 {
+        let (_, parent, direction) = &nodes[index];
+        path.push(*direction);
+        index = *parent;
+    }
+    path.reverse();
+    path
+}
+
+pub fn format_solution(path: &[Direction]) -> String {
+    path.iter().map(|direction| direction.arrow()).collect()
+}
+
+// 求解谜题包：rust_2048_game puzzle FILE [--limit N]
+#[cfg(feature = "serde")]
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut path = None;
+    let mut limit = DEFAULT_SOLVE_LIMIT;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--limit" => limit = crate::headless::parse_value(arg, iter.next())?,
+            arg if !arg.starts_with("--") && path.is_none() => path = Some(arg.to_string()),
+            _ => return Err(format!("未知参数: {}", arg).into()),
+        }
+    }
+    let pack = match path {
+        Some(path) => PuzzlePack::load(&path)?,
+        None => PuzzlePack::builtin(),
+    };
+
+    println!("谜题包: {}", pack.name);
+    for puzzle in &pack.puzzles {
+        let result = match solve(puzzle, limit, &AtomicBool::new(false)) {
+            Solution::Solved(path) => format!("{}步: {}", path.len(), format_solution(&path)),
+            Solution::Unsolvable => "无解".to_string(),
+            Solution::LimitReached => "超过搜索上限".to_string(),
+        };
+        println!("{}（{}，{}步内）: {}", puzzle.name, puzzle.goal.label(), puzzle.max_moves, result);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle(board: &str, seed: Option<u64>, goal: Goal, max_moves: usize) -> Puzzle {
+        Puzzle {
+            name: "test".to_string(),
+            start: board.parse().unwrap(),
+            seed,
+            goal,
+            max_moves,
+        }
+    }
+
+    #[test]
+    fn test_parse_goal() {
+        for goal in [Goal::Tile(64), Goal::ClearTo(1), Goal::Score(500)] {
+            assert_eq!(goal.to_string().parse::<Goal>(), Ok(goal));
+        }
+        assert!("tile".parse::<Goal>().is_err());
+        assert!("moves:3".parse::<Goal>().is_err());
+    }
+
+    #[test]
+    fn test_solver_finds_shortest_solution_or_proves_none() {
+        // 左移三次即可把一行合成16
+        let p = puzzle("2 2 4 8\n. . . .\n. . . .\n. . . .", None, Goal::Tile(16), 3);
+        let Solution::Solved(path) = solve(&p, DEFAULT_SOLVE_LIMIT, &AtomicBool::new(false)) else {
+            panic!("应当有解");
+        };
+        assert_eq!(path.len(), 3);
+        let mut game = PuzzleGame::new(p.clone());
+        for direction in path {
+            assert!(game.apply(direction));
+        }
+        assert_eq!(game.status(), PuzzleStatus::Solved);
+
+        // 总和只有16，不可能合成32
+        let p = puzzle("2 2 4 8\n. . . .\n. . . .\n. . . .", None, Goal::Tile(32), 10);
+        assert_eq!(solve(&p, DEFAULT_SOLVE_LIMIT, &AtomicBool::new(false)), Solution::Unsolvable);
+        assert_eq!(solve(&p, DEFAULT_SOLVE_LIMIT, &AtomicBool::new(true)), Solution::LimitReached);
+    }
+
+    #[test]
+    fn test_spawns_are_deterministic() {
+        let p = puzzle("2 . . .\n. . . .\n. . . 4\n. . . .", Some(7), Goal::Score(1_000), 20);
+        let mut a = PuzzleGame::new(p.clone());
+        let mut b = PuzzleGame::new(p);
+        for direction in [Direction::Left, Direction::Up, Direction::Right, Direction::Down] {
+            assert_eq!(a.apply(direction), b.apply(direction));
+            assert_eq!(a.board(), b.board());
+        }
+    }
+}
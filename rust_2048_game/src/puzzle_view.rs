@@ -0,0 +1,290 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+use eframe::egui;
+use egui::{Color32, Vec2};
+#[cfg(feature = "serde")]
+use log::error;
+
+use crate::board::Direction;
+use crate::board_view::{self, BOARD_SIZE};
+use crate::puzzle::{self, PuzzleGame, PuzzlePack, PuzzleProgress, PuzzleStatus, Solution};
+
+// 谜题页面：选择谜题包中的谜题手动求解，也可以让求解器给出最短解法并逐步演示
+pub struct PuzzleView {
+    packs: Vec<PuzzlePack>,
+    pack: usize,
+    selected: Option<usize>,
+    game: Option<PuzzleGame>,
+    progress: PuzzleProgress,
+    // 按解法演示过的尝试不计入已解开
+    assisted: bool,
+    solution: Option<Solution>,
+    receiver: Option<Receiver<Solution>>,
+    cancel: Arc<AtomicBool>,
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    path: String,
+    message: Option<String>,
+}
+
+impl PuzzleView {
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut view = Self {
+            packs: Vec::new(),
+            pack: 0,
+            selected: None,
+            game: None,
+            progress: PuzzleProgress::default(),
+            assisted: false,
+            solution: None,
+            receiver: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            path: String::new(),
+            message: None,
+        };
+        #[cfg(feature = "serde")]
+        {
+            view.packs.push(PuzzlePack::builtin());
+            match crate::storage::load_json::<PuzzleProgress>(puzzle::PROGRESS_FILE) {
+                Ok(progress) => view.progress = progress.unwrap_or_default(),
+                Err(e) => error!("读取谜题进度失败: {}", e),
+            }
+        }
+        view
+    }
+
+    // 后台求解时需要轮询结果
+    pub fn is_solving(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = Some(index);
+        self.restart();
+        self.solution = None;
+        self.stop_solving();
+    }
+
+    fn clear_selection(&mut self) {
+        self.selected = None;
+        self.game = None;
+        self.solution = None;
+        self.stop_solving();
+    }
+
+    // 让后台的求解尽快结束，结果不再需要
+    fn stop_solving(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.receiver = None;
+    }
+
+    fn restart(&mut self) {
+        self.game = self
+            .selected
+            .map(|index| PuzzleGame::new(self.packs[self.pack].puzzles[index].clone()));
+        self.assisted = false;
+    }
+
+    fn apply(&mut self, direction: Direction) {
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        if !game.apply(direction) || game.status() != PuzzleStatus::Solved || self.assisted {
+            return;
+        }
+        let key = PuzzleProgress::key(&self.packs[self.pack], game.puzzle());
+        let moves = game.history().len();
+        if self.progress.record(key, moves) {
+            #[cfg(feature = "serde")]
+            if let Err(e) = crate::storage::save_json(puzzle::PROGRESS_FILE, &self.progress) {
+                error!("保存谜题进度失败: {}", e);
+            }
+        }
+    }
+
+    pub fn handle_input(&mut self, ctx: &egui::Context) {
+        let pressed = ctx.input(|i| {
+            [
+                (egui::Key::ArrowUp, Direction::Up),
+                (egui::Key::ArrowDown, Direction::Down),
+                (egui::Key::ArrowLeft, Direction::Left),
+                (egui::Key::ArrowRight, Direction::Right),
+            ]
+            .into_iter()
+            .find(|(key, _)| i.key_pressed(*key))
+            .map(|(_, direction)| direction)
+        });
+        if let Some(direction) = pressed {
+            self.apply(direction);
+        }
+    }
+
+    fn start_solving(&mut self) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let current = game.puzzle().clone();
+        self.stop_solving();
+        let cancel = self.cancel.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // 切换谜题后接收端已被丢弃，发送失败可以忽略
+            let _ = sender.send(puzzle::solve(&current, puzzle::DEFAULT_SOLVE_LIMIT, &cancel));
+        });
+        self.solution = None;
+        self.receiver = Some(receiver);
+    }
+
+    fn poll(&mut self) {
+        if let Some(solution) = self.receiver.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            self.solution = Some(solution);
+            self.receiver = None;
+        }
+    }
+
+    // 从开局按解法走到下一步
+    fn demo_step(&mut self, path: &[Direction]) {
+        let Some(game) = &self.game else {
+            return;
+        };
+        let moves = game.history().len();
+        if path.get(..moves) != Some(game.history()) {
+            self.restart();
+        }
+        self.assisted = true;
+        let moves = self.game.as_ref().map_or(0, |game| game.history().len());
+        if let Some(&direction) = path.get(moves) {
+            self.apply(direction);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn draw_load(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("谜题包文件:");
+            ui.text_edit_singleline(&mut self.path);
+            if ui.button("加载").clicked() {
+                self.message = Some(match PuzzlePack::load(self.path.trim()) {
+                    Ok(pack) => {
+                        let message = format!("已加载 {}（{}道谜题）", pack.name, pack.puzzles.len());
+                        self.packs.push(pack);
+                        self.pack = self.packs.len() - 1;
+                        self.clear_selection();
+                        message
+                    }
+                    Err(e) => format!("加载失败: {}", e),
+                });
+            }
+        });
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        ui.vertical_centered(|ui| {
+            #[cfg(feature = "serde")]
+            self.draw_load(ui);
+            #[cfg(not(feature = "serde"))]
+            ui.label("加载谜题包需要启用 serde 特性");
+            if let Some(message) = &self.message {
+                ui.label(message);
+            }
+            if self.packs.is_empty() {
+                return;
+            }
+
+            let pack = self.pack;
+            egui::ComboBox::from_label("谜题包")
+                .selected_text(&self.packs[self.pack].name)
+                .show_ui(ui, |ui| {
+                    for (index, pack) in self.packs.iter().enumerate() {
+                        ui.selectable_value(&mut self.pack, index, &pack.name);
+                    }
+                });
+            if self.pack != pack {
+                self.clear_selection();
+            }
+
+            let mut clicked = None;
+            ui.horizontal_wrapped(|ui| {
+                let pack = &self.packs[self.pack];
+                for (index, puzzle) in pack.puzzles.iter().enumerate() {
+                    let best = self.progress.best(&PuzzleProgress::key(pack, puzzle));
+                    let text = match best {
+                        Some(moves) => format!("✔ {}（{}步）", puzzle.name, moves),
+                        None => puzzle.name.clone(),
+                    };
+                    if ui.selectable_label(self.selected == Some(index), text).clicked() {
+                        clicked = Some(index);
+                    }
+                }
+            });
+            if let Some(index) = clicked {
+                self.select(index);
+            }
+
+            let Some(game) = &self.game else {
+                ui.label("选择一道谜题开始");
+                return;
+            };
+            let current = game.puzzle();
+            ui.heading(&current.name);
+            ui.label(format!("目标: {}，{}步以内", current.goal.label(), current.max_moves));
+            if current.seed.is_none() {
+                ui.label("移动后不生成新方块");
+            }
+            ui.label(format!("剩余步数: {}  分数: {}", game.moves_left(), game.board().score));
+            match game.status() {
+                PuzzleStatus::Solved if self.assisted => {
+                    ui.colored_label(Color32::GREEN, "演示完成");
+                }
+                PuzzleStatus::Solved => {
+                    ui.colored_label(Color32::GREEN, format!("解开了！用了{}步", game.history().len()));
+                }
+                PuzzleStatus::Failed => {
+                    ui.colored_label(Color32::RED, "失败，请重新开始");
+                }
+                PuzzleStatus::Playing => {}
+            }
+            if !game.history().is_empty() {
+                ui.label(format!("已走: {}", puzzle::format_solution(game.history())));
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("重新开始").clicked() {
+                    self.restart();
+                }
+                if ui.add_enabled(!self.is_solving(), egui::Button::new("求解")).clicked() {
+                    self.start_solving();
+                }
+            });
+            if self.is_solving() {
+                ui.label("求解中...");
+            }
+            match self.solution.clone() {
+                Some(Solution::Solved(path)) => {
+                    ui.label(format!("最短解法 {}步: {}", path.len(), puzzle::format_solution(&path)));
+                    if ui.button("演示下一步").clicked() {
+                        self.demo_step(&path);
+                    }
+                }
+                Some(Solution::Unsolvable) => {
+                    ui.colored_label(Color32::RED, "已穷举所有走法，这道谜题无解");
+                }
+                Some(Solution::LimitReached) => {
+                    ui.label("局面太多，超过了搜索上限，未能得出结论");
+                }
+                None => {}
+            }
+
+            if let Some(game) = &self.game {
+                let (rect, _) = ui.allocate_exact_size(Vec2::splat(BOARD_SIZE), egui::Sense::hover());
+                board_view::draw_board(ui.painter(), rect, game.board());
+            }
+        });
+    }
+}
@@ -17,7 +17,7 @@ impl GameRecorder {
         let screens = Screen::all().unwrap();
         Self {
             frames: Vec::new(),
-            screen: screens[0].clone(),
+            screen: screens[0],
             start_x: x+10,
             start_y: y,
             width,
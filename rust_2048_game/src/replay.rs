@@ -0,0 +1,112 @@
+use crate::board::{Board, Direction};
+use crate::session::GameSession;
+
+// 一步移动的说明：方向、得分、合并次数以及是否出现了新的最大方块
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveNote {
+    pub direction: Direction,
+    pub score_delta: u32,
+    pub merges: usize,
+    pub new_max: Option<u32>,
+}
+
+impl MoveNote {
+    fn new(before: &Board, direction: Direction, after: &Board) -> Self {
+        // 每次合并让方块数减一，用滑动后、出块前的棋盘计算
+        let mut slid = before.clone();
+        slid.slide(direction);
+        let max_tile = after.max_tile();
+        Self {
+            direction,
+            score_delta: after.score - before.score,
+            merges: before.tile_count() - slid.tile_count(),
+            new_max: (max_tile > before.max_tile()).then_some(max_tile),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} +{}", self.direction.arrow(), self.score_delta);
+        if self.merges > 0 {
+            text.push_str(&format!(" 合并{}次", self.merges));
+        }
+        if let Some(tile) = self.new_max {
+            text.push_str(&format!(" 新的最大方块 {}", tile));
+        }
+        text
+    }
+}
+
+// 一局游戏的完整回放：第 i 个棋盘是走完第 i 步之后的局面，第0个是开局
+pub struct Replay {
+    boards: Vec<Board>,
+    notes: Vec<MoveNote>,
+}
+
+impl Replay {
+    pub fn from_session(session: &GameSession) -> Self {
+        let boards = session.boards();
+        let notes = session
+            .history()
+            .iter()
+            .zip(boards.windows(2))
+            .map(|(&direction, pair)| MoveNote::new(&pair[0], direction, &pair[1]))
+            .collect();
+        Self { boards, notes }
+    }
+
+    // 总步数，位置的取值范围是 0..=len
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    pub fn board(&self, position: usize) -> &Board {
+        &self.boards[position.min(self.len())]
+    }
+
+    // 走到这个位置的那一步，开局没有说明
+    pub fn note(&self, position: usize) -> Option<&MoveNote> {
+        position.checked_sub(1).and_then(|index| self.notes.get(index))
+    }
+
+    pub fn notes(&self) -> &[MoveNote] {
+        &self.notes
+    }
+
+    pub fn scores(&self) -> impl Iterator<Item = u32> + '_ {
+        self.boards.iter().map(|board| board.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_matches_session() {
+        let mut session = GameSession::new(5);
+        for direction in Direction::ALL.into_iter().cycle().take(60) {
+            session.apply(direction);
+        }
+        let replay = Replay::from_session(&session);
+        assert_eq!(replay.len(), session.moves());
+        assert_eq!(replay.board(0), &session.boards()[0]);
+        assert_eq!(replay.board(replay.len()), session.board());
+        assert_eq!(replay.scores().last(), Some(session.score()));
+        assert!(replay.note(0).is_none());
+
+        let total: u32 = replay.notes().iter().map(|note| note.score_delta).sum();
+        assert_eq!(total, session.score());
+    }
+
+    #[test]
+    fn test_move_note_counts_merges() {
+        let before = Board::from_cells([[2, 2, 4, 4], [0, 0, 0, 0], [8, 0, 0, 0], [0, 0, 0, 0]]);
+        let mut after = before.clone();
+        after.slide(Direction::Left);
+        let note = MoveNote::new(&before, Direction::Left, &after);
+        assert_eq!(note.merges, 2);
+        assert_eq!(note.score_delta, 12);
+        assert_eq!(note.new_max, None);
+        assert_eq!(note.describe(), "← +12 合并2次");
+    }
+}
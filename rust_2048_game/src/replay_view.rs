@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+use eframe::egui;
+use egui::{Color32, Pos2, Sense, Shape, Stroke, Vec2};
+
+use crate::board_view::{self, BOARD_SIZE};
+use crate::replay::Replay;
+use crate::session::GameSession;
+
+const GRAPH_HEIGHT: f32 = 80.0;
+const GRAPH_WIDTH: f32 = 400.0;
+
+// 回放页面：拖动时间轴查看任意一步，可以按速度自动播放或单步前进后退
+pub struct ReplayView {
+    replay: Option<Replay>,
+    position: usize,
+    playing: bool,
+    // 每秒播放的步数
+    speed: f64,
+    last_step: f64,
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    path: String,
+    message: Option<String>,
+}
+
+impl ReplayView {
+    pub fn new() -> Self {
+        Self {
+            replay: None,
+            position: 0,
+            playing: false,
+            speed: 5.0,
+            last_step: 0.0,
+            path: String::new(),
+            message: None,
+        }
+    }
+
+    fn open(&mut self, session: &GameSession) {
+        self.replay = Some(Replay::from_session(session));
+        self.position = 0;
+        self.playing = false;
+    }
+
+    // 回放文件可以是无界面运行 --save 保存的对局，也可以是存档槽位或自动存档
+    #[cfg(feature = "serde")]
+    fn load(path: &str) -> Result<GameSession, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        match serde_json::from_str::<GameSession>(&text) {
+            Ok(session) => Ok(session),
+            Err(e) => match serde_json::from_str::<crate::storage::SaveGame>(&text) {
+                Ok(save) => Ok(save.session),
+                Err(_) => Err(e.into()),
+            },
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn draw_load(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("回放文件:");
+            ui.text_edit_singleline(&mut self.path);
+            if ui.button("加载").clicked() {
+                match Self::load(self.path.trim()) {
+                    Ok(session) => {
+                        self.open(&session);
+                        self.message = Some(format!("已加载，共{}步", session.moves()));
+                    }
+                    Err(e) => self.message = Some(format!("加载失败: {}", e)),
+                }
+            }
+        });
+    }
+
+    pub fn repaint_after(&self, now: f64) -> Option<Duration> {
+        self.playing
+            .then(|| Duration::from_secs_f64((self.last_step + 1.0 / self.speed - now).max(0.0)))
+    }
+
+    // 播放时按速度前进，可能一帧前进多步，到最后一步时停止
+    fn advance(&mut self, now: f64) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        if !self.playing {
+            self.last_step = now;
+            return;
+        }
+        let steps = ((now - self.last_step) * self.speed) as usize;
+        if steps > 0 {
+            self.last_step += steps as f64 / self.speed;
+            self.position = (self.position + steps).min(replay.len());
+        }
+        if self.position >= replay.len() {
+            self.playing = false;
+        }
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, now: f64, current: &GameSession) {
+        self.advance(now);
+
+        ui.vertical_centered(|ui| {
+            ui.horizontal(|ui| {
+                if ui.button("查看当前对局").clicked() {
+                    self.open(current);
+                    self.message = None;
+                }
+                #[cfg(feature = "serde")]
+                self.draw_load(ui);
+            });
+            if let Some(message) = &self.message {
+                ui.label(message);
+            }
+        });
+
+        let Some(replay) = &self.replay else {
+            ui.vertical_centered(|ui| ui.label("加载回放文件或查看当前对局"));
+            return;
+        };
+        let len = replay.len();
+
+        ui.vertical_centered(|ui| {
+            ui.add(egui::Slider::new(&mut self.position, 0..=len).text("步"));
+            ui.horizontal(|ui| {
+                if ui.button("⏮").clicked() {
+                    self.position = 0;
+                }
+                if ui.button("◀").clicked() {
+                    self.position = self.position.saturating_sub(1);
+                    self.playing = false;
+                }
+                let label = if self.playing { "⏸" } else { "▶" };
+                if ui.button(label).clicked() {
+                    self.playing = !self.playing;
+                    if self.playing && self.position >= len {
+                        self.position = 0;
+                    }
+                    self.last_step = now;
+                }
+                if ui.button("▶|").clicked() {
+                    self.position = (self.position + 1).min(len);
+                    self.playing = false;
+                }
+                if ui.button("⏭").clicked() {
+                    self.position = len;
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.speed, 0.5..=50.0)
+                        .logarithmic(true)
+                        .suffix("步/秒")
+                        .text("速度"),
+                );
+            });
+
+            let board = replay.board(self.position);
+            ui.label(format!("第{}/{}步  分数: {}", self.position, len, board.score));
+            match replay.note(self.position) {
+                Some(note) => ui.label(note.describe()),
+                None => ui.label("开局"),
+            };
+
+            if let Some(position) = draw_score_graph(ui, replay, self.position) {
+                self.position = position;
+                self.playing = false;
+            }
+        });
+
+        let board_rect = board_view::board_rect(ui);
+        board_view::draw_board(ui.painter(), board_rect, replay.board(self.position));
+        ui.allocate_space(Vec2::new(ui.available_width(), BOARD_SIZE + 30.0));
+
+        // 每一步的说明，点击跳到这一步
+        let mut jump = None;
+        egui::ScrollArea::vertical().max_height(200.0).show_rows(
+            ui,
+            ui.text_style_height(&egui::TextStyle::Body),
+            len,
+            |ui, rows| {
+                for index in rows {
+                    let note = &replay.notes()[index];
+                    let text = format!("第{}步 {}", index + 1, note.describe());
+                    if ui.selectable_label(self.position == index + 1, text).clicked() {
+                        jump = Some(index + 1);
+                    }
+                }
+            },
+        );
+        if let Some(position) = jump {
+            self.position = position;
+            self.playing = false;
+        }
+    }
+}
+
+// 分数随步数变化的小图，竖线标出当前位置；返回点击的位置
+fn draw_score_graph(ui: &mut egui::Ui, replay: &Replay, position: usize) -> Option<usize> {
+    let width = ui.available_width().min(GRAPH_WIDTH);
+    let (rect, response) = ui.allocate_exact_size(Vec2::new(width, GRAPH_HEIGHT), Sense::click());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 2.0, Stroke::new(1.0, Color32::GRAY));
+
+    let len = replay.len().max(1);
+    let max_score = replay.scores().max().unwrap_or(0).max(1) as f32;
+    let x_at = |index: usize| rect.left() + rect.width() * index as f32 / len as f32;
+    // 步数很多时每个像素只取一个点
+    let stride = (replay.len() / rect.width().max(1.0) as usize).max(1);
+    let points: Vec<Pos2> = replay
+        .scores()
+        .enumerate()
+        .filter(|(index, _)| index % stride == 0 || *index == replay.len())
+        .map(|(index, score)| Pos2::new(x_at(index), rect.bottom() - rect.height() * score as f32 / max_score))
+        .collect();
+    painter.add(Shape::line(points, Stroke::new(1.5, Color32::from_rgb(237, 194, 46))));
+
+    let x = x_at(position);
+    painter.line_segment(
+        [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+        Stroke::new(1.0, Color32::LIGHT_BLUE),
+    );
+
+    let pos = response.interact_pointer_pos().filter(|_| response.clicked())?;
+    let fraction = ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+    Some((fraction * replay.len() as f32).round() as usize)
+}
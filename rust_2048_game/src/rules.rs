@@ -0,0 +1,145 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::merge::MergeRule;
+
+// 一局游戏的胜负规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRules {
+    // 第一次出现不小于这个值的方块时获胜
+    pub target: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub merge: MergeRule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub challenge: Challenge,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self::for_variant(MergeRule::Classic)
+    }
+}
+
+impl GameRules {
+    pub const ARGS: [&'static str; 3] = ["--target", "--variant", "--challenge"];
+
+    pub fn for_variant(merge: MergeRule) -> Self {
+        Self {
+            target: merge.default_target(),
+            merge,
+            challenge: Challenge::Endless,
+        }
+    }
+
+    pub fn parse_arg<'a>(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<(), String> {
+        use crate::headless::parse_value;
+
+        match arg {
+            "--target" => {
+                let target: u32 = parse_value(arg, args.next())?;
+                if target < 3 {
+                    return Err(format!("目标方块至少为3: {}", target));
+                }
+                self.target = target;
+            }
+            // 没有单独指定目标时使用变体的默认目标
+            "--variant" => {
+                let merge: MergeRule = parse_value(arg, args.next())?;
+                if self.target == self.merge.default_target() {
+                    self.target = merge.default_target();
+                }
+                self.merge = merge;
+            }
+            "--challenge" => self.challenge = parse_value(arg, args.next())?,
+            _ => return Err(format!("未知参数: {}", arg)),
+        }
+        Ok(())
+    }
+}
+
+// 挑战模式：限时模式在时间用完或达成目标时结束，限步模式在走完规定步数时结束
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Challenge {
+    #[default]
+    Endless,
+    TimeAttack { seconds: u32 },
+    MoveLimit { moves: usize },
+}
+
+impl Challenge {
+    pub fn label(self) -> String {
+        match self {
+            Challenge::Endless => "无尽".to_string(),
+            Challenge::TimeAttack { seconds } => format!("限时{}秒", seconds),
+            Challenge::MoveLimit { moves } => format!("限{}步", moves),
+        }
+    }
+}
+
+// 命令行格式：endless、time:秒数、moves:步数
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Challenge::Endless => write!(f, "endless"),
+            Challenge::TimeAttack { seconds } => write!(f, "time:{}", seconds),
+            Challenge::MoveLimit { moves } => write!(f, "moves:{}", moves),
+        }
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("未知挑战模式: {}", s);
+        match s.split_once(':') {
+            None if s == "endless" => Ok(Challenge::Endless),
+            Some(("time", seconds)) => match seconds.parse() {
+                Ok(seconds) if seconds > 0 => Ok(Challenge::TimeAttack { seconds }),
+                _ => Err(invalid()),
+            },
+            Some(("moves", moves)) => match moves.parse() {
+                Ok(moves) if moves > 0 => Ok(Challenge::MoveLimit { moves }),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// 对局状态：达到目标后停在 Won，玩家选择继续后进入 Continuing，直到无路可走或挑战结束
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Continuing,
+    Over,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_challenge() {
+        for challenge in [
+            Challenge::Endless,
+            Challenge::TimeAttack { seconds: 60 },
+            Challenge::MoveLimit { moves: 100 },
+        ] {
+            assert_eq!(challenge.to_string().parse::<Challenge>(), Ok(challenge));
+        }
+        assert!("time:0".parse::<Challenge>().is_err());
+        assert!("moves".parse::<Challenge>().is_err());
+        assert!("blitz:5".parse::<Challenge>().is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use eframe::egui;
+
+use crate::merge::MergeRule;
+use crate::rules::{Challenge, GameRules};
+
+// 胜负规则设置界面：变体、获胜目标和挑战模式；返回设置是否有变化
+pub fn draw(ui: &mut egui::Ui, rules: &mut GameRules) -> bool {
+    let before = *rules;
+    // 切换变体时获胜目标回到该变体的默认值
+    if draw_variant(ui, &mut rules.merge) {
+        rules.target = rules.merge.default_target();
+    }
+    ui.horizontal(|ui| {
+        ui.label("获胜目标");
+        for target in rules.merge.target_presets() {
+            ui.radio_value(&mut rules.target, target, target.to_string());
+        }
+        ui.add(egui::DragValue::new(&mut rules.target).clamp_range(3..=196_418));
+    });
+    draw_challenge(ui, &mut rules.challenge);
+    *rules != before
+}
+
+fn draw_variant(ui: &mut egui::Ui, merge: &mut MergeRule) -> bool {
+    let before = *merge;
+    ui.horizontal(|ui| {
+        ui.label("变体");
+        for rule in MergeRule::ALL {
+            ui.radio_value(merge, rule, rule.label());
+        }
+    });
+    *merge != before
+}
+
+fn draw_challenge(ui: &mut egui::Ui, challenge: &mut Challenge) {
+    ui.horizontal(|ui| {
+        ui.label("模式");
+        if ui.radio(*challenge == Challenge::Endless, "无尽").clicked() {
+            *challenge = Challenge::Endless;
+        }
+        if ui.radio(matches!(challenge, Challenge::TimeAttack { .. }), "限时").clicked()
+            && !matches!(challenge, Challenge::TimeAttack { .. })
+        {
+            *challenge = Challenge::TimeAttack { seconds: 120 };
+        }
+        if ui.radio(matches!(challenge, Challenge::MoveLimit { .. }), "限步").clicked()
+            && !matches!(challenge, Challenge::MoveLimit { .. })
+        {
+            *challenge = Challenge::MoveLimit { moves: 200 };
+        }
+        match challenge {
+            Challenge::Endless => {}
+            Challenge::TimeAttack { seconds } => {
+                ui.add(egui::DragValue::new(seconds).clamp_range(10..=3600).suffix("秒"));
+            }
+            Challenge::MoveLimit { moves } => {
+                ui.add(egui::DragValue::new(moves).clamp_range(10..=10_000).suffix("步"));
+            }
+        }
+    });
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use eframe::egui;
+
+// 极速模式下按帧批量移动时的重绘间隔
+const TURBO_REPAINT: Duration = Duration::from_millis(33);
+const MAX_INTERVAL: f64 = 5.0;
+
+// 自动测试的运行控制：暂停、单步、速度以及停止条件
+pub struct RunControl {
+    pub paused: bool,
+    step_requested: bool,
+    // 移动间隔（秒），0 表示极速模式
+    pub interval: f64,
+    pub stop_after_games: Option<u32>,
+    pub target_tile: Option<u32>,
+    games_played: u32,
+    target_hit: bool,
+    last_move_time: f64,
+    pub stop_reason: Option<String>,
+}
+
+impl RunControl {
+    pub fn new(interval: f64) -> Self {
+        Self {
+            paused: false,
+            step_requested: false,
+            interval,
+            stop_after_games: None,
+            target_tile: None,
+            games_played: 0,
+            target_hit: false,
+            last_move_time: 0.0,
+            stop_reason: None,
+        }
+    }
+
+    pub fn is_turbo(&self) -> bool {
+        self.interval <= 0.0
+    }
+
+    // 本帧允许的移动次数；极速模式下由调用方按时间预算截断
+    pub fn moves_due(&mut self, now: f64) -> usize {
+        if self.paused {
+            return if std::mem::take(&mut self.step_requested) { 1 } else { 0 };
+        }
+        if self.is_turbo() {
+            self.last_move_time = now;
+            return usize::MAX;
+        }
+        if now - self.last_move_time >= self.interval {
+            self.last_move_time = now;
+            1
+        } else {
+            0
+        }
+    }
+
+    // 一局结束时调用，达到局数限制返回 true 并暂停
+    pub fn game_finished(&mut self) -> bool {
+        self.games_played += 1;
+        self.target_hit = false;
+        if let Some(limit) = self.stop_after_games {
+            if self.games_played >= limit {
+                self.stop(format!("已完成{}局", self.games_played));
+                return true;
+            }
+        }
+        false
+    }
+
+    // 每局只在第一次达到目标方块时暂停一次
+    pub fn check_target(&mut self, max_tile: u32) -> bool {
+        match self.target_tile {
+            Some(target) if !self.target_hit && max_tile >= target => {
+                self.target_hit = true;
+                self.stop(format!("已达成目标方块 {}", target));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn stop(&mut self, reason: String) {
+        self.paused = true;
+        self.stop_reason = Some(reason);
+    }
+
+    fn resume(&mut self) {
+        self.paused = false;
+        self.stop_reason = None;
+        if self.stop_after_games.is_some_and(|limit| self.games_played >= limit) {
+            self.games_played = 0;
+        }
+    }
+
+    // 暂停时不主动重绘，只在输入事件时刷新
+    pub fn repaint_after(&self, now: f64) -> Option<Duration> {
+        if self.paused {
+            None
+        } else if self.is_turbo() {
+            Some(TURBO_REPAINT)
+        } else {
+            let remaining = (self.last_move_time + self.interval - now).max(0.0);
+            Some(Duration::from_secs_f64(remaining))
+        }
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = if self.paused { "继续" } else { "暂停" };
+            if ui.button(label).clicked() {
+                if self.paused {
+                    self.resume();
+                } else {
+                    self.paused = true;
+                }
+            }
+            if ui.add_enabled(self.paused, egui::Button::new("单步")).clicked() {
+                self.step_requested = true;
+            }
+            ui.add(
+                egui::Slider::new(&mut self.interval, 0.0..=MAX_INTERVAL)
+                    .logarithmic(true)
+                    .suffix("秒")
+                    .text("间隔"),
+            );
+        });
+        if self.is_turbo() {
+            ui.label("极速模式");
+        }
+
+        ui.horizontal(|ui| {
+            optional_limit(ui, "局数后停止", &mut self.stop_after_games, 10, 1..=10_000);
+            optional_limit(ui, "目标方块", &mut self.target_tile, 2048, 4..=65_536);
+        });
+
+        if let Some(reason) = &self.stop_reason {
+            ui.label(reason);
+        }
+    }
+}
+
+fn optional_limit(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<u32>,
+    default: u32,
+    range: std::ops::RangeInclusive<u32>,
+) {
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then_some(default);
+    }
+    if let Some(limit) = value {
+        ui.add(egui::DragValue::new(limit).clamp_range(range));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_and_single_step() {
+        let mut control = RunControl::new(0.5);
+        assert_eq!(control.moves_due(0.6), 1);
+        assert_eq!(control.moves_due(0.8), 0);
+        assert_eq!(control.moves_due(1.1), 1);
+
+        control.paused = true;
+        assert_eq!(control.moves_due(5.0), 0);
+        control.step_requested = true;
+        assert_eq!(control.moves_due(5.0), 1);
+        assert_eq!(control.moves_due(5.0), 0);
+        assert_eq!(control.repaint_after(5.0), None);
+    }
+
+    #[test]
+    fn test_stop_conditions() {
+        let mut control = RunControl::new(0.0);
+        control.stop_after_games = Some(2);
+        control.target_tile = Some(512);
+
+        assert!(!control.check_target(256));
+        assert!(control.check_target(512));
+        assert!(control.paused);
+        control.resume();
+        // 同一局不会再次因为目标方块暂停
+        assert!(!control.check_target(1024));
+
+        assert!(!control.game_finished());
+        assert!(control.game_finished());
+        assert!(control.paused);
+        control.resume();
+        assert!(!control.game_finished());
+    }
+}
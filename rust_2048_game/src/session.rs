@@ -0,0 +1,465 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Direction};
+use crate::game::Playable;
+use crate::rules::{Challenge, GameRules, GameStatus};
+use crate::spawner::{SpawnPolicy, SpawnRules};
+
+// 一局游戏：种子 + 起始棋盘 + 移动历史即可完整复现整局
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "SessionRecord", try_from = "SessionRecord")
+)]
+pub struct GameSession {
+    seed: u64,
+    start: Board,
+    history: Vec<Direction>,
+    board: Board,
+    spawn: SpawnPolicy,
+    spawn_rules: SpawnRules,
+    rules: GameRules,
+    // 第一次达到目标时的步数
+    won_at: Option<usize>,
+    continued: bool,
+    // 累计游戏时间（秒），由调用方按实际经过的时间推进，限时模式据此结束
+    elapsed: f64,
+    rng: StdRng,
+}
+
+impl GameSession {
+    pub fn new(seed: u64) -> Self {
+        Self::with_rules(seed, GameRules::default(), SpawnRules::CLASSIC)
+    }
+
+    // 变体和出块规则决定了初始棋盘，因此在构造时给出
+    pub fn with_rules(seed: u64, rules: GameRules, spawn_rules: SpawnRules) -> Self {
+        // 初始棋盘与后续出块使用不同的随机流
+        let mut start_rng = StdRng::seed_from_u64(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let start = Board::new_with_rules(rules.merge, &spawn_rules, &mut start_rng);
+        let mut session = Self::from_board(start, seed).with_game_rules(rules);
+        session.spawn_rules = spawn_rules;
+        session
+    }
+
+    pub fn from_board(start: Board, seed: u64) -> Self {
+        Self {
+            seed,
+            board: start.clone(),
+            start,
+            history: Vec::new(),
+            spawn: SpawnPolicy::Random,
+            spawn_rules: SpawnRules::CLASSIC,
+            rules: GameRules::default(),
+            won_at: None,
+            continued: false,
+            elapsed: 0.0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // 出块方式属于对局的一部分，只能在开局时设置
+    pub fn with_spawn_policy(mut self, spawn: SpawnPolicy) -> Self {
+        assert!(self.history.is_empty(), "对局开始后不能修改出块方式");
+        self.spawn = spawn;
+        self
+    }
+
+    pub fn with_spawn_rules(mut self, spawn_rules: SpawnRules) -> Self {
+        assert!(self.history.is_empty(), "对局开始后不能修改出块规则");
+        self.spawn_rules = spawn_rules;
+        self
+    }
+
+    // 棋盘上的合并规则以规则对象为准
+    pub fn with_game_rules(mut self, rules: GameRules) -> Self {
+        assert!(self.history.is_empty(), "对局开始后不能修改规则");
+        self.rules = rules;
+        self.start = self.start.with_merge_rule(rules.merge);
+        self.board = self.board.with_merge_rule(rules.merge);
+        self
+    }
+
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    pub fn spawn_policy(&self) -> SpawnPolicy {
+        self.spawn
+    }
+
+    pub fn spawn_rules(&self) -> &SpawnRules {
+        &self.spawn_rules
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn history(&self) -> &[Direction] {
+        &self.history
+    }
+
+    pub fn score(&self) -> u32 {
+        self.board.score
+    }
+
+    pub fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.board.is_game_over() || self.challenge_over()
+    }
+
+    // 限时模式在时间用完或达成目标时结束，限步模式在走完规定步数时结束
+    pub fn challenge_over(&self) -> bool {
+        match self.rules.challenge {
+            Challenge::Endless => false,
+            Challenge::TimeAttack { seconds } => self.elapsed >= seconds as f64 || self.won_at.is_some(),
+            Challenge::MoveLimit { moves } => self.history.len() >= moves,
+        }
+    }
+
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    // 推进游戏时间，对局结束后不再计时
+    pub fn tick(&mut self, seconds: f64) {
+        if !self.is_game_over() {
+            self.elapsed += seconds;
+        }
+    }
+
+    // 界面上显示的挑战进度，例如剩余时间或剩余步数
+    pub fn challenge_progress(&self) -> Option<String> {
+        match self.rules.challenge {
+            Challenge::Endless => None,
+            Challenge::TimeAttack { seconds } => {
+                Some(format!("剩余时间: {:.1}秒", (seconds as f64 - self.elapsed).max(0.0)))
+            }
+            Challenge::MoveLimit { moves } => Some(format!("剩余步数: {}", moves.saturating_sub(self.history.len()))),
+        }
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if self.is_game_over() {
+            GameStatus::Over
+        } else if self.won_at.is_none() {
+            GameStatus::Playing
+        } else if self.continued {
+            GameStatus::Continuing
+        } else {
+            GameStatus::Won
+        }
+    }
+
+    pub fn has_won(&self) -> bool {
+        self.won_at.is_some()
+    }
+
+    // 获胜后需要先选择继续才能接着移动
+    pub fn continue_playing(&mut self) {
+        if self.won_at.is_some() {
+            self.continued = true;
+        }
+    }
+
+    // 只记录真正改变了棋盘的移动，无效移动不会消耗随机数
+    // 第一次达到目标时进入 Won 状态，之后不会再次触发
+    pub fn apply(&mut self, direction: Direction) -> bool {
+        if self.status() == GameStatus::Won || self.challenge_over() {
+            return false;
+        }
+        let moved = self.board.slide(direction);
+        if moved {
+            self.spawn.spawn(&mut self.board, &self.spawn_rules, &mut self.rng);
+            self.history.push(direction);
+            let every = self.spawn_rules.blocker_every;
+            if every > 0 && self.history.len().is_multiple_of(every) {
+                self.board.add_blocker(&self.spawn_rules, &mut self.rng);
+            }
+            if self.won_at.is_none() && self.board.has_reached(self.rules.target) {
+                self.won_at = Some(self.history.len());
+            }
+        }
+        moved
+    }
+
+    // 从起始棋盘按历史重放，依次返回开局和每一步之后的棋盘
+    pub fn boards(&self) -> Vec<Board> {
+        let mut session = Self::from_board(self.start.clone(), self.seed).with_spawn_policy(self.spawn);
+        session.spawn_rules = self.spawn_rules;
+        session.rules = self.rules;
+        let mut boards = Vec::with_capacity(self.history.len() + 1);
+        boards.push(session.board.clone());
+        for &direction in &self.history {
+            if session.status() == GameStatus::Won {
+                session.continue_playing();
+            }
+            session.apply(direction);
+            boards.push(session.board.clone());
+        }
+        boards
+    }
+
+    #[cfg(feature = "serde")]
+    fn replay(record: &SessionRecord) -> Self {
+        let mut session = Self::from_board(record.start.clone(), record.seed).with_spawn_policy(record.spawn);
+        session.spawn_rules = record.spawn_rules;
+        session.rules = record.rules;
+        for &direction in &record.history {
+            // 存档里获胜之后的移动说明玩家当时选择了继续
+            if session.status() == GameStatus::Won {
+                session.continue_playing();
+            }
+            session.apply(direction);
+        }
+        if record.continued {
+            session.continue_playing();
+        }
+        session.elapsed = record.elapsed;
+        session
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+}
+
+// 自动玩家达到目标后总是继续，直到无路可走；限时模式按实际经过的时间计时，包括策略思考的时间
+impl Playable for GameSession {
+    type Board = Board;
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn board(&self) -> &Board {
+        &self.board
+    }
+
+    fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    fn is_game_over(&self) -> bool {
+        GameSession::is_game_over(self)
+    }
+
+    fn has_won(&self) -> bool {
+        GameSession::has_won(self)
+    }
+
+    fn before_move(&mut self, elapsed: f64) {
+        if self.status() == GameStatus::Won {
+            self.continue_playing();
+        }
+        self.tick(elapsed);
+    }
+
+    fn apply(&mut self, direction: Direction) -> bool {
+        GameSession::apply(self, direction)
+    }
+}
+
+// 存档格式：随机数状态不直接保存，读取时按历史重放恢复
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SessionRecord {
+    seed: u64,
+    start: Board,
+    history: Vec<Direction>,
+    // 旧存档没有这个字段，按经典随机出块处理
+    #[serde(default)]
+    spawn: SpawnPolicy,
+    #[serde(default)]
+    spawn_rules: SpawnRules,
+    #[serde(default)]
+    rules: GameRules,
+    #[serde(default)]
+    continued: bool,
+    #[serde(default)]
+    elapsed: f64,
+    score: u32,
+    board: Board,
+}
+
+#[cfg(feature = "serde")]
+impl From<GameSession> for SessionRecord {
+    fn from(session: GameSession) -> Self {
+        Self {
+            seed: session.seed,
+            score: session.board.score,
+            start: session.start,
+            history: session.history,
+            spawn: session.spawn,
+            spawn_rules: session.spawn_rules,
+            rules: session.rules,
+            continued: session.continued,
+            elapsed: session.elapsed,
+            board: session.board,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SessionRecord> for GameSession {
+    type Error = String;
+
+    fn try_from(record: SessionRecord) -> Result<Self, Self::Error> {
+        record.spawn_rules.validate()?;
+        let session = GameSession::replay(&record);
+        if session.history.len() != record.history.len() {
+            return Err("存档中包含无效移动".to_string());
+        }
+        if session.board != record.board || session.board.score != record.score {
+            return Err("存档棋盘与回放结果不一致".to_string());
+        }
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let mut a = GameSession::new(42);
+        let mut b = GameSession::new(42);
+        for direction in [Direction::Left, Direction::Up, Direction::Right, Direction::Down] {
+            a.apply(direction);
+            b.apply(direction);
+        }
+        assert_eq!(a.board(), b.board());
+        assert_eq!(a.history(), b.history());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_roundtrip_resumes_rng() {
+        let mut session = GameSession::new(3);
+        for direction in [Direction::Left, Direction::Down, Direction::Left, Direction::Down] {
+            session.apply(direction);
+        }
+
+        let json = serde_json::to_string(&session).unwrap();
+        let mut loaded: GameSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.board(), session.board());
+
+        // 读取后的随机数状态应与原局一致
+        for direction in Direction::ALL {
+            assert_eq!(loaded.apply(direction), session.apply(direction));
+            assert_eq!(loaded.board(), session.board());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_tampered_save_is_rejected() {
+        let mut session = GameSession::new(5);
+        session.apply(Direction::Left);
+        session.apply(Direction::Up);
+
+        let mut value = serde_json::to_value(&session).unwrap();
+        value["score"] = serde_json::json!(99999);
+        assert!(serde_json::from_value::<GameSession>(value).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_spawn_settings_are_saved() {
+        let rules = SpawnRules {
+            tiles_per_move: 2,
+            ..SpawnRules::CLASSIC
+        };
+        let mut session = GameSession::with_rules(8, GameRules::default(), rules).with_spawn_policy(SpawnPolicy::Evil);
+        for direction in [Direction::Left, Direction::Up, Direction::Right, Direction::Down] {
+            session.apply(direction);
+        }
+
+        let json = serde_json::to_string(&session).unwrap();
+        let loaded: GameSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.spawn_policy(), SpawnPolicy::Evil);
+        assert_eq!(loaded.spawn_rules(), &rules);
+        assert_eq!(loaded.board(), session.board());
+    }
+
+    #[test]
+    fn test_win_is_reported_once_and_needs_continue() {
+        let start = Board::from_cells([
+            [2, 2, 0, 0],
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+        ]);
+        let rules = GameRules {
+            target: 4,
+            ..GameRules::default()
+        };
+        let mut session = GameSession::from_board(start, 1).with_game_rules(rules);
+        assert_eq!(session.status(), GameStatus::Playing);
+
+        assert!(session.apply(Direction::Left));
+        assert_eq!(session.status(), GameStatus::Won);
+        // 选择继续之前不能移动
+        let moves = session.moves();
+        for direction in Direction::ALL {
+            assert!(!session.apply(direction));
+        }
+        assert_eq!(session.moves(), moves);
+
+        session.continue_playing();
+        assert_eq!(session.status(), GameStatus::Continuing);
+        assert!(Direction::ALL.into_iter().any(|direction| session.apply(direction)));
+        assert_ne!(session.status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn test_challenge_modes_end_the_game() {
+        let limited = GameRules {
+            challenge: Challenge::MoveLimit { moves: 3 },
+            ..GameRules::default()
+        };
+        let mut session = GameSession::with_rules(3, limited, SpawnRules::CLASSIC);
+        while session.moves() < 3 {
+            assert!(Direction::ALL.into_iter().any(|direction| session.apply(direction)));
+        }
+        assert!(session.is_game_over());
+        assert_eq!(session.status(), GameStatus::Over);
+        assert!(Direction::ALL.into_iter().all(|direction| !session.apply(direction)));
+        assert_eq!(session.challenge_progress().as_deref(), Some("剩余步数: 0"));
+
+        let timed = GameRules {
+            challenge: Challenge::TimeAttack { seconds: 10 },
+            ..GameRules::default()
+        };
+        let mut session = GameSession::with_rules(3, timed, SpawnRules::CLASSIC);
+        session.tick(4.0);
+        assert!(!session.is_game_over());
+        session.tick(6.5);
+        assert!(session.is_game_over());
+        // 结束后不再计时
+        session.tick(5.0);
+        assert_eq!(session.elapsed(), 10.5);
+    }
+}
@@ -0,0 +1,45 @@
+use eframe::egui;
+
+use crate::merge::MergeRule;
+use crate::spawner::{SpawnPolicy, SpawnRules};
+
+// 出块方式设置界面，返回设置是否有变化
+pub fn draw_policy(ui: &mut egui::Ui, policy: &mut SpawnPolicy) -> bool {
+    let before = *policy;
+    ui.horizontal(|ui| {
+        ui.label("出块");
+        for option in SpawnPolicy::ALL {
+            ui.radio_value(policy, option, option.label());
+        }
+    });
+    *policy != before
+}
+
+// 出块规则设置界面，权重按变体的出块数值显示；返回设置是否有变化
+pub fn draw_rules(ui: &mut egui::Ui, rules: &mut SpawnRules, merge: MergeRule) -> bool {
+    let before = *rules;
+    ui.horizontal(|ui| {
+        ui.label("权重");
+        for (value, weight) in merge.spawn_values().into_iter().zip(rules.weights.iter_mut()) {
+            ui.add(egui::DragValue::new(weight).clamp_range(0..=100).prefix(format!("{}: ", value)));
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut rules.tiles_per_move).clamp_range(1..=4).prefix("每步生成 "));
+        ui.add(
+            egui::DragValue::new(&mut rules.initial_tiles)
+                .clamp_range(1..=SpawnRules::MAX_INITIAL_TILES)
+                .prefix("开局方块 "),
+        );
+        ui.checkbox(&mut rules.edges_only, "只在边缘生成");
+    });
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut rules.blockers).clamp_range(0..=6).prefix("开局障碍 "));
+        ui.add(egui::DragValue::new(&mut rules.blocker_every).clamp_range(0..=200).prefix("每隔步数加障碍 "));
+    });
+    // 权重不能全部为0
+    if rules.weights.iter().all(|&weight| weight == 0) {
+        rules.weights = before.weights;
+    }
+    *rules != before
+}
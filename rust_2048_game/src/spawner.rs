@@ -0,0 +1,327 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::prelude::SliceRandom;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::strategy::{afterstates, heuristic};
+
+// 新方块的规则：可能的数值及权重、每步生成几个、开局几个、是否只在边缘生成，以及障碍格
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpawnRules {
+    // 依次对应变体的四种出块数值，经典规则下是2、4、8、16
+    pub weights: [u32; 4],
+    pub tiles_per_move: usize,
+    pub initial_tiles: usize,
+    pub edges_only: bool,
+    // 开局放置的障碍数，以及每隔多少步再放一个障碍（0表示不放）
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blockers: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blocker_every: usize,
+}
+
+impl Default for SpawnRules {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+impl SpawnRules {
+    // 经典规则：90% 为2，10% 为4，每步一个，开局两个
+    pub const CLASSIC: SpawnRules = SpawnRules {
+        weights: [9, 1, 0, 0],
+        tiles_per_move: 1,
+        initial_tiles: 2,
+        edges_only: false,
+        blockers: 0,
+        blocker_every: 0,
+    };
+
+    // 开局方块数的上限，界面和命令行相同
+    pub const MAX_INITIAL_TILES: usize = 8;
+
+    pub const ARGS: [&'static str; 6] = [
+        "--spawn-weights",
+        "--spawn-count",
+        "--initial-tiles",
+        "--edge-spawn",
+        "--blockers",
+        "--blocker-every",
+    ];
+
+    // 权重不为0的数值
+    pub fn values(&self, values: [u32; 4]) -> impl Iterator<Item = u32> + '_ {
+        values
+            .into_iter()
+            .zip(self.weights)
+            .filter(|&(_, weight)| weight > 0)
+            .map(|(value, _)| value)
+    }
+
+    // 只有两种数值时按伯努利分布抽取，经典规则的随机数序列与原来的 gen_bool(0.9) 一致
+    pub fn pick_value<R: Rng + ?Sized>(&self, values: [u32; 4], rng: &mut R) -> u32 {
+        let choices: Vec<(u32, u32)> = values
+            .into_iter()
+            .zip(self.weights)
+            .filter(|&(_, weight)| weight > 0)
+            .collect();
+        match choices[..] {
+            [] => values[0],
+            [(value, _)] => value,
+            [(first, a), (second, b)] => {
+                if rng.gen_bool(a as f64 / (a + b) as f64) { first } else { second }
+            }
+            _ => {
+                let total: u32 = choices.iter().map(|&(_, weight)| weight).sum();
+                let mut roll = rng.gen_range(0..total);
+                for &(value, weight) in &choices {
+                    if roll < weight {
+                        return value;
+                    }
+                    roll -= weight;
+                }
+                unreachable!()
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.weights.iter().all(|&weight| weight == 0) {
+            return Err("至少需要一种数值的权重大于0".to_string());
+        }
+        if self.tiles_per_move == 0 || self.initial_tiles == 0 || self.initial_tiles > Self::MAX_INITIAL_TILES {
+            return Err(format!("每步生成数需大于0，开局方块数需在1到{}之间", Self::MAX_INITIAL_TILES));
+        }
+        if self.initial_tiles + self.blockers > 16 {
+            return Err("开局方块和障碍加起来不能超过16个".to_string());
+        }
+        Ok(())
+    }
+
+    // 解析命令行参数：--spawn-weights 9,1,0,0、--spawn-count N、--initial-tiles N、--edge-spawn、
+    // --blockers N、--blocker-every N
+    // 权重按变体的出块数值排列
+    pub fn parse_arg<'a>(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<(), String> {
+        use crate::headless::parse_value;
+
+        match arg {
+            "--spawn-weights" => {
+                let text: String = parse_value(arg, args.next())?;
+                let weights: Vec<u32> = text
+                    .split(',')
+                    .map(|weight| weight.trim().parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("参数 {} 的取值无效: {}", arg, text))?;
+                if weights.is_empty() || weights.len() > 4 {
+                    return Err(format!("参数 {} 需要1到4个权重，依次对应变体的出块数值", arg));
+                }
+                self.weights = [0; 4];
+                self.weights[..weights.len()].copy_from_slice(&weights);
+            }
+            "--spawn-count" => self.tiles_per_move = parse_value(arg, args.next())?,
+            "--initial-tiles" => self.initial_tiles = parse_value(arg, args.next())?,
+            "--edge-spawn" => self.edges_only = true,
+            "--blockers" => self.blockers = parse_value(arg, args.next())?,
+            "--blocker-every" => self.blocker_every = parse_value(arg, args.next())?,
+            _ => return Err(format!("未知参数: {}", arg)),
+        }
+        self.validate()
+    }
+}
+
+// 移动后新方块的生成方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SpawnPolicy {
+    // 经典规则：随机空格，90% 为2，10% 为4
+    #[default]
+    Random,
+    // 放在让玩家下一步最佳结果最差的位置
+    Evil,
+    // 放在让玩家下一步最佳结果最好的位置
+    Nice,
+}
+
+impl SpawnPolicy {
+    pub const ALL: [SpawnPolicy; 3] = [SpawnPolicy::Random, SpawnPolicy::Evil, SpawnPolicy::Nice];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpawnPolicy::Random => "随机",
+            SpawnPolicy::Evil => "刁难",
+            SpawnPolicy::Nice => "友好",
+        }
+    }
+
+    // 按规则放置一步的新方块
+    pub fn spawn<R: Rng + ?Sized>(self, board: &mut Board, rules: &SpawnRules, rng: &mut R) {
+        for _ in 0..rules.tiles_per_move {
+            self.spawn_one(board, rules, rng);
+        }
+    }
+
+    fn spawn_one<R: Rng + ?Sized>(self, board: &mut Board, rules: &SpawnRules, rng: &mut R) {
+        let evil = match self {
+            SpawnPolicy::Random => return board.add_tile_with_rules(rules, rng),
+            SpawnPolicy::Evil => true,
+            SpawnPolicy::Nice => false,
+        };
+
+        // 有预告时数值已经确定，只能选择位置
+        let values: Vec<u32> = match board.next_tile() {
+            Some(next) => vec![next],
+            None => rules.values(board.merge_rule().spawn_values()).collect(),
+        };
+        // 先打乱候选位置，估值相同时的选择仍由种子决定
+        let mut placements: Vec<(usize, usize, u32)> = board
+            .spawn_cells(rules)
+            .into_iter()
+            .flat_map(|(row, col)| values.iter().map(move |&value| (row, col, value)))
+            .collect();
+        placements.shuffle(rng);
+
+        let scored = placements.into_iter().map(|(row, col, value)| {
+            let mut next = board.clone();
+            next.set_cell(row, col, value);
+            (row, col, value, player_value(&next))
+        });
+        let chosen = if evil {
+            scored.min_by(|a, b| a.3.total_cmp(&b.3))
+        } else {
+            scored.max_by(|a, b| a.3.total_cmp(&b.3))
+        };
+        if let Some((row, col, value, _)) = chosen {
+            board.place_spawned(row, col, value, rules, rng);
+        }
+    }
+}
+
+// 一层搜索：玩家在放置新方块后能走出的最佳局面，无路可走时为负无穷
+fn player_value(board: &Board) -> f64 {
+    afterstates(board)
+        .into_iter()
+        .map(|(_, _, after)| (after.score - board.score) as f64 + heuristic(&after))
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+impl fmt::Display for SpawnPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpawnPolicy::Random => "random",
+            SpawnPolicy::Evil => "evil",
+            SpawnPolicy::Nice => "nice",
+        })
+    }
+}
+
+impl FromStr for SpawnPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(SpawnPolicy::Random),
+            "evil" => Ok(SpawnPolicy::Evil),
+            "nice" => Ok(SpawnPolicy::Nice),
+            other => Err(format!("未知出块方式: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::MergeRule;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_evil_and_nice_pick_extreme_placements() {
+        let board = Board::from_cells([
+            [2, 4, 8, 0],
+            [4, 8, 16, 32],
+            [8, 16, 32, 64],
+            [16, 32, 64, 0],
+        ]);
+        let mut all = Vec::new();
+        for (row, col) in board.empty_cells() {
+            for value in [2, 4] {
+                let mut next = board.clone();
+                next.set_cell(row, col, value);
+                all.push(player_value(&next));
+            }
+        }
+        let worst = all.iter().copied().fold(f64::INFINITY, f64::min);
+        let best = all.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut evil = board.clone();
+        SpawnPolicy::Evil.spawn(&mut evil, &SpawnRules::CLASSIC, &mut rng);
+        let mut nice = board.clone();
+        SpawnPolicy::Nice.spawn(&mut nice, &SpawnRules::CLASSIC, &mut rng);
+
+        assert_eq!(evil.empty_cells().len(), 1);
+        assert_eq!(player_value(&evil), worst);
+        assert_eq!(player_value(&nice), best);
+        assert!(worst < best);
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        for policy in SpawnPolicy::ALL {
+            assert_eq!(policy.to_string().parse::<SpawnPolicy>(), Ok(policy));
+        }
+        assert!("hard".parse::<SpawnPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_spawn_rules() {
+        let rules = SpawnRules {
+            weights: [0, 0, 1, 0],
+            tiles_per_move: 2,
+            initial_tiles: 3,
+            edges_only: true,
+            ..SpawnRules::CLASSIC
+        };
+        // 新方块只在边缘的格子上，数值都是8
+        let on_edge = |row: usize, col: usize| row == 0 || row == 3 || col == 0 || col == 3;
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut board = Board::new_with_rules(MergeRule::Classic, &rules, &mut rng);
+            assert_eq!(board.empty_cells().len(), 13);
+            for (row, col) in (0..16).map(|index| (index / 4, index % 4)) {
+                let value = board.get_cell(row, col);
+                assert!(value == 0 || (value == 8 && on_edge(row, col)));
+            }
+
+            // 只比较生成前后的棋盘，滑动本身可能把方块移到中间
+            board.slide(crate::board::Direction::Left);
+            let before = board.clone();
+            SpawnPolicy::Random.spawn(&mut board, &rules, &mut rng);
+            let spawned: Vec<(usize, usize)> = (0..16)
+                .map(|index| (index / 4, index % 4))
+                .filter(|&(row, col)| board.get_cell(row, col) != before.get_cell(row, col))
+                .collect();
+            assert_eq!(spawned.len(), 2);
+            for (row, col) in spawned {
+                assert_eq!(before.get_cell(row, col), 0);
+                assert_eq!(board.get_cell(row, col), 8);
+                assert!(on_edge(row, col));
+            }
+        }
+
+        let mut parsed = SpawnRules::CLASSIC;
+        let args: Vec<String> = vec!["0,1,1".to_string()];
+        parsed.parse_arg("--spawn-weights", &mut args.iter()).unwrap();
+        assert_eq!(parsed.weights, [0, 1, 1, 0]);
+        assert!(parsed.parse_arg("--spawn-weights", &mut ["0".to_string()].iter()).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// 多局运行的累计统计
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameStats {
+    pub total_games: u32,
+    pub total_score: u64,
+    pub max_score: u32,
+}
+
+impl GameStats {
+    pub fn record(&mut self, score: u32) {
+        self.total_games += 1;
+        self.total_score += score as u64;
+        self.max_score = self.max_score.max(score);
+    }
+
+    pub fn average(&self) -> Option<f64> {
+        (self.total_games > 0).then(|| self.total_score as f64 / self.total_games as f64)
+    }
+}
+
+// 滑动平均，前几个点使用已有数据的平均值
+pub fn rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    let mut averages = Vec::with_capacity(values.len());
+    for (i, value) in values.iter().enumerate() {
+        sum += value;
+        if i >= window {
+            sum -= values[i - window];
+        }
+        averages.push(sum / (i + 1).min(window) as f64);
+    }
+    averages
+}
+
+// 把数据等宽分成若干区间，返回每个区间的 (起点, 终点, 数量)
+pub fn histogram(values: &[f64], buckets: usize) -> Vec<(f64, f64, usize)> {
+    if values.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = ((max - min) / buckets as f64).max(1.0);
+
+    let mut counts = vec![0; buckets];
+    for value in values {
+        let index = (((value - min) / width) as usize).min(buckets - 1);
+        counts[index] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (min + i as f64 * width, min + (i + 1) as f64 * width, count))
+        .collect()
+}
+
+// 已排序数据的分位数，相邻两点之间线性插值
+pub fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64))
+}
+
+// 平均值和样本标准差
+pub fn mean_std(values: &[f64]) -> Option<(f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = if values.len() > 1 {
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    Some((mean, variance.sqrt()))
+}
+
+// 配对检验的结果：difference 为 a - b 的平均差
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairedTest {
+    pub difference: f64,
+    pub std_error: f64,
+    pub p_value: f64,
+}
+
+// 同一组种子上两组结果的配对检验，样本较多时 t 分布近似为正态分布
+pub fn paired_test(a: &[f64], b: &[f64]) -> Option<PairedTest> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let differences: Vec<f64> = a.iter().zip(b).map(|(a, b)| a - b).collect();
+    let (difference, std) = mean_std(&differences)?;
+    let std_error = std / (differences.len() as f64).sqrt();
+    let p_value = if std_error == 0.0 {
+        if difference == 0.0 { 1.0 } else { 0.0 }
+    } else {
+        erfc((difference / std_error).abs() / std::f64::consts::SQRT_2)
+    };
+    Some(PairedTest {
+        difference,
+        std_error,
+        p_value,
+    })
+}
+
+// 互补误差函数（Abramowitz-Stegun 7.1.26，误差小于 1.5e-7），x >= 0
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    poly * (-x * x).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_is_not_truncated() {
+        let mut stats = GameStats::default();
+        stats.record(3);
+        stats.record(4);
+        assert_eq!(stats.average(), Some(3.5));
+        assert_eq!(GameStats::default().average(), None);
+    }
+
+    #[test]
+    fn test_rolling_average() {
+        let averages = rolling_average(&[2.0, 4.0, 6.0, 8.0], 2);
+        assert_eq!(averages, vec![2.0, 3.0, 5.0, 7.0]);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let buckets = histogram(&[0.0, 1.0, 5.0, 9.0, 10.0], 2);
+        assert_eq!(buckets, vec![(0.0, 5.0, 2), (5.0, 10.0, 3)]);
+        assert!(histogram(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_quantile_and_paired_test() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(quantile(&sorted, 0.5), Some(2.5));
+        assert_eq!(quantile(&sorted, 1.0), Some(4.0));
+        assert_eq!(quantile(&[], 0.5), None);
+
+        let same = paired_test(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]).unwrap();
+        assert_eq!((same.difference, same.p_value), (0.0, 1.0));
+
+        // 差值为 1, 3, 1, 3：标准误 sqrt(4/3)/2，z ≈ 3.46，双侧 p ≈ 5.3e-4
+        let test = paired_test(&[3.0, 4.0, 5.0, 6.0], &[2.0, 1.0, 4.0, 3.0]).unwrap();
+        assert!((test.difference - 2.0).abs() < 1e-9);
+        assert!((test.p_value - 5.32e-4).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use egui::Color32;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+
+use crate::highscores::{GameRecord, HighScoreStore};
+use crate::merge::MergeRule;
+use crate::stats;
+
+const CHART_HEIGHT: f32 = 140.0;
+
+// 统计面板：分数曲线、最大方块分布、步数分布
+pub struct StatsView {
+    // None 表示显示全部策略
+    filter: Option<String>,
+    // 不同变体的分数不能相比，每次只显示一种变体
+    merge: MergeRule,
+    window: usize,
+}
+
+impl StatsView {
+    pub fn new() -> Self {
+        Self {
+            filter: None,
+            merge: MergeRule::Classic,
+            window: 20,
+        }
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, store: &HighScoreStore) {
+        let mut players: Vec<&str> = store.records.iter().map(|r| r.player.as_str()).collect();
+        players.sort_unstable();
+        players.dedup();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("变体")
+                .selected_text(self.merge.label())
+                .show_ui(ui, |ui| {
+                    for merge in store.variants() {
+                        ui.selectable_value(&mut self.merge, merge, merge.label());
+                    }
+                });
+            egui::ComboBox::from_label("策略")
+                .selected_text(self.filter.as_deref().unwrap_or("全部"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter, None, "全部");
+                    for player in players {
+                        ui.selectable_value(&mut self.filter, Some(player.to_string()), player);
+                    }
+                });
+            ui.add(egui::Slider::new(&mut self.window, 1..=100).text("滑动窗口"));
+        });
+
+        let records: Vec<&GameRecord> = store
+            .records
+            .iter()
+            .filter(|r| r.merge == self.merge && self.filter.as_ref().is_none_or(|f| &r.player == f))
+            .collect();
+        if records.is_empty() {
+            ui.label("暂无数据");
+            return;
+        }
+
+        let scores: Vec<f64> = records.iter().map(|r| r.score as f64).collect();
+        let rolling = stats::rolling_average(&scores, self.window);
+        let wins = records.iter().filter(|r| r.won()).count();
+
+        ui.label(format!(
+            "场次: {}  平均分数: {:.1}  最近{}局平均: {:.1}  胜率: {:.1}%",
+            records.len(),
+            scores.iter().sum::<f64>() / scores.len() as f64,
+            self.window.min(scores.len()),
+            rolling.last().copied().unwrap_or(0.0),
+            wins as f64 * 100.0 / records.len() as f64
+        ));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.strong("分数曲线");
+            score_chart(ui, &scores, &rolling);
+
+            ui.strong("最大方块分布");
+            let mut tiles: BTreeMap<u32, usize> = BTreeMap::new();
+            for record in &records {
+                *tiles.entry(record.max_tile).or_default() += 1;
+            }
+            let bars: Vec<(String, usize)> =
+                tiles.into_iter().map(|(tile, count)| (tile.to_string(), count)).collect();
+            bar_chart(ui, "tile_chart", bars);
+
+            ui.strong("步数分布");
+            let moves: Vec<f64> = records.iter().map(|r| r.moves as f64).collect();
+            let bars: Vec<(String, usize)> = stats::histogram(&moves, 10)
+                .into_iter()
+                .map(|(start, _, count)| (format!("{:.0}", start), count))
+                .collect();
+            bar_chart(ui, "moves_chart", bars);
+        });
+    }
+}
+
+fn chart(id: &str) -> Plot {
+    // 图表放在滚动区域里，滚轮留给滚动区域
+    Plot::new(id).height(CHART_HEIGHT).allow_scroll(false).include_y(0.0)
+}
+
+fn series(values: &[f64]) -> PlotPoints {
+    values.iter().enumerate().map(|(i, &value)| [i as f64, value]).collect()
+}
+
+fn score_chart(ui: &mut egui::Ui, scores: &[f64], rolling: &[f64]) {
+    chart("score_chart").legend(Legend::default()).show(ui, |plot_ui| {
+        plot_ui.line(Line::new(series(scores)).color(Color32::from_rgb(100, 150, 250)).name("分数"));
+        plot_ui.line(Line::new(series(rolling)).color(Color32::from_rgb(246, 124, 95)).name("滑动平均"));
+    });
+}
+
+// 横轴为柱子的序号，刻度显示对应的标签
+fn bar_chart(ui: &mut egui::Ui, id: &str, bars: Vec<(String, usize)>) {
+    let chart_bars: Vec<Bar> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, (label, count))| Bar::new(i as f64, *count as f64).width(0.8).name(label))
+        .collect();
+    let labels: Vec<String> = bars.into_iter().map(|(label, _)| label).collect();
+    chart(id)
+        .x_axis_formatter(move |value, _, _| {
+            let index = value.round();
+            if (value - index).abs() < 1e-6 && index >= 0.0 {
+                labels.get(index as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(chart_bars).color(Color32::from_rgb(237, 194, 46)));
+        });
+}
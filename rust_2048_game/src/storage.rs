@@ -0,0 +1,57 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::daily::DailyRun;
+use crate::session::GameSession;
+use crate::stats::GameStats;
+
+pub const AUTOSAVE: &str = "autosave.json";
+pub const SAVE_SLOTS: usize = 3;
+
+// 存档内容：当前对局（含历史）与累计统计
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub session: GameSession,
+    pub stats: GameStats,
+    // 当前对局是每日挑战时记录日期，读取后继续计入当天的成绩
+    #[serde(default)]
+    pub daily: Option<DailyRun>,
+    // 从编辑器的局面开始的对局不计入最高分和统计
+    #[serde(default)]
+    pub edited: bool,
+}
+
+pub fn slot_file(slot: usize) -> String {
+    format!("slot_{}.json", slot)
+}
+
+// 用户数据目录，例如 Windows 下的 %APPDATA%\rust_2048_game
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rust_2048_game")
+}
+
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir)?;
+    // 先写临时文件再重命名，避免退出时写到一半损坏存档
+    let tmp = dir.join(format!("{}.tmp", name));
+    serde_json::to_writer_pretty(File::create(&tmp)?, value)?;
+    fs::rename(tmp, dir.join(name))?;
+    Ok(())
+}
+
+// 文件不存在时返回 Ok(None)
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Result<Option<T>, Box<dyn std::error::Error>> {
+    let path = data_dir().join(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = File::open(path)?;
+    Ok(Some(serde_json::from_reader(BufReader::new(file))?))
+}
@@ -0,0 +1,412 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::board::Board;
+use crate::game::Game;
+use crate::ntuple::DEFAULT_WEIGHTS_FILE;
+
+// 自动玩家，通过 Game 接口下方形、六边形和立方体棋盘
+pub trait Strategy<G: Game = Board>: Send {
+    fn name(&self) -> &str;
+
+    // 按 Game::MOVES 的顺序给出每个方向的评估值，不能移动的方向为 None
+    fn evaluate(&mut self, board: &G) -> Vec<Option<f64>>;
+
+    // 耗时的策略在标志被设置后应尽快结束本次计算
+    fn set_cancel_flag(&mut self, _cancel: Arc<AtomicBool>) {}
+
+    fn choose(&mut self, board: &G) -> Option<G::Move> {
+        best_move::<G>(&self.evaluate(board))
+    }
+}
+
+// 可以移动的方向及其移动后的棋盘（尚未生成新方块），下标为方向在 Game::MOVES 中的位置
+pub fn afterstates<G: Game>(board: &G) -> Vec<(usize, G::Move, G)> {
+    G::MOVES
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &direction)| {
+            let mut next = board.clone();
+            next.slide(direction).then_some((index, direction, next))
+        })
+        .collect()
+}
+
+// 按 Game::MOVES 的顺序选出评估值最高的方向
+fn best_move<G: Game>(values: &[Option<f64>]) -> Option<G::Move> {
+    G::MOVES
+        .iter()
+        .zip(values)
+        .filter_map(|(&direction, value)| value.map(|value| (direction, value)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(direction, _)| direction)
+}
+
+// 静态局面估值：空格越多、可合并的相邻方块越多、各行列越单调越好
+pub fn heuristic(board: &Board) -> f64 {
+    let rule = board.merge_rule();
+
+    let mut value = 0.0;
+    for i in 0..4 {
+        let lines = [[0, 1, 2, 3].map(|j| (i, j)), [0, 1, 2, 3].map(|j| (j, i))];
+        for cells in lines {
+            let tiles = cells.map(|(row, col)| board.get_cell(row, col));
+            // 障碍格的数值为0，但不算空格
+            let empty = cells
+                .iter()
+                .filter(|&&(row, col)| board.get_cell(row, col) == 0 && !board.is_blocker(row, col))
+                .count();
+            let merges = tiles.windows(2).filter(|pair| rule.merge(pair[0], pair[1]).is_some()).count();
+            // 按方块在变体序列中的序号计算单调性，经典规则下即指数
+            let line = tiles.map(|tile| rule.rank(tile) as f64);
+            let (mut increasing, mut decreasing) = (0.0, 0.0);
+            for pair in line.windows(2) {
+                if pair[0] > pair[1] {
+                    decreasing += pair[0] * pair[0] - pair[1] * pair[1];
+                } else {
+                    increasing += pair[1] * pair[1] - pair[0] * pair[0];
+                }
+            }
+            value += 135.0 * empty as f64 + 350.0 * merges as f64 - 23.5 * f64::min(increasing, decreasing);
+        }
+    }
+    value
+}
+
+// 随机选择可以移动的方向
+pub struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<G: Game> Strategy<G> for RandomStrategy {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn evaluate(&mut self, board: &G) -> Vec<Option<f64>> {
+        let mut values = vec![None; G::MOVES.len()];
+        for (index, _, _) in afterstates(board) {
+            values[index] = Some(0.0);
+        }
+        values
+    }
+
+    fn choose(&mut self, board: &G) -> Option<G::Move> {
+        afterstates(board)
+            .choose(&mut self.rng)
+            .map(|&(_, direction, _)| direction)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsConfig {
+    // 每个方向的模拟次数
+    pub rollouts: usize,
+    // 每次模拟最多走的步数，None 表示直到游戏结束
+    pub depth_limit: Option<usize>,
+    // 每步的思考时间上限，到时后使用已完成的模拟结果
+    pub time_budget: Option<Duration>,
+    pub threads: usize,
+    // 模拟时优先选择得分最多的方向，而不是完全随机
+    pub guided: bool,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            rollouts: 100,
+            depth_limit: None,
+            time_budget: Some(Duration::from_millis(200)),
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            guided: false,
+        }
+    }
+}
+
+// 蒙特卡洛模拟：对每个方向的移动结果做多次随机模拟，选平均最终分数最高的方向
+pub struct MctsStrategy {
+    config: MctsConfig,
+    rng: StdRng,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl MctsStrategy {
+    pub fn new(config: MctsConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            cancel: None,
+        }
+    }
+
+    // 按 Game::MOVES 的顺序给出每个方向的平均模拟分数，不能移动的方向为 None
+    fn move_values<G: Game>(&mut self, board: &G) -> Vec<Option<f64>> {
+        let candidates = afterstates(board);
+        let mut values = vec![None; G::MOVES.len()];
+        if candidates.is_empty() {
+            return values;
+        }
+        let cancel = self.cancel.as_deref();
+
+        let deadline = self.config.time_budget.map(|budget| Instant::now() + budget);
+        let threads = self.config.threads.clamp(1, self.config.rollouts.max(1));
+        let seeds: Vec<u64> = (0..threads).map(|_| self.rng.gen()).collect();
+
+        // 每个线程轮流为所有方向做模拟，时间用完时各方向的模拟次数保持均衡
+        let totals: Vec<Vec<(f64, usize)>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = seeds
+                .iter()
+                .enumerate()
+                .map(|(thread, &seed)| {
+                    let candidates = &candidates;
+                    let config = &self.config;
+                    let count = config.rollouts / threads + usize::from(thread < config.rollouts % threads);
+                    scope.spawn(move || {
+                        let mut rng = StdRng::seed_from_u64(seed);
+                        let mut totals = vec![(0.0, 0); candidates.len()];
+                        for _ in 0..count {
+                            if deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                                || cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+                            {
+                                break;
+                            }
+                            for (total, (_, _, afterstate)) in totals.iter_mut().zip(candidates) {
+                                total.0 += rollout(afterstate, config, &mut rng) as f64;
+                                total.1 += 1;
+                            }
+                        }
+                        totals
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        for (i, &(index, _, ref afterstate)) in candidates.iter().enumerate() {
+            let (sum, count) = totals
+                .iter()
+                .fold((0.0, 0), |acc, thread| (acc.0 + thread[i].0, acc.1 + thread[i].1));
+            // 时间预算太小一次模拟都没完成时，退化为移动后的分数
+            values[index] = Some(if count > 0 { sum / count as f64 } else { afterstate.score() as f64 });
+        }
+        values
+    }
+}
+
+impl<G: Game> Strategy<G> for MctsStrategy {
+    fn name(&self) -> &str {
+        "mcts"
+    }
+
+    fn set_cancel_flag(&mut self, cancel: Arc<AtomicBool>) {
+        self.cancel = Some(cancel);
+    }
+
+    fn evaluate(&mut self, board: &G) -> Vec<Option<f64>> {
+        self.move_values(board)
+    }
+}
+
+fn rollout<G: Game, R: Rng + ?Sized>(afterstate: &G, config: &MctsConfig, rng: &mut R) -> u32 {
+    let mut board = afterstate.clone();
+    board.add_random_tile(rng);
+
+    let mut depth = 0;
+    while config.depth_limit.is_none_or(|limit| depth < limit) {
+        let mut next_states = afterstates(&board);
+        if next_states.is_empty() {
+            break;
+        }
+        let next = if config.guided {
+            next_states.shuffle(rng);
+            next_states.into_iter().max_by_key(|(_, _, next)| next.score()).unwrap().2
+        } else {
+            next_states.swap_remove(rng.gen_range(0..next_states.len())).2
+        };
+        board = next;
+        board.add_random_tile(rng);
+        depth += 1;
+    }
+    board.score()
+}
+
+// 可在界面和命令行中选择的策略配置
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrategyKind {
+    Random,
+    Mcts(MctsConfig),
+    // 使用训练好的 n-tuple 网络权重文件
+    NTuple { weights: String },
+}
+
+impl StrategyKind {
+    // n-tuple 网络只针对方形棋盘，其它形状的棋盘由 Game::ntuple_strategy 返回错误
+    pub fn build<G: Game>(&self, seed: u64) -> Result<Box<dyn Strategy<G>>, String> {
+        Ok(match self {
+            StrategyKind::Random => Box::new(RandomStrategy::new(seed)),
+            StrategyKind::Mcts(config) => Box::new(MctsStrategy::new(config.clone(), seed)),
+            StrategyKind::NTuple { weights } => G::ntuple_strategy(weights)?,
+        })
+    }
+
+    // 随机策略不评估局面，所有方向的估值相同，不能用来给出提示
+    pub fn evaluates(&self) -> bool {
+        !matches!(self, StrategyKind::Random)
+    }
+
+    pub const ARGS: [&'static str; 7] = [
+        "--strategy",
+        "--rollouts",
+        "--depth",
+        "--time-ms",
+        "--threads",
+        "--guided",
+        "--weights",
+    ];
+
+    // 解析命令行参数：--strategy random|mcts|ntuple、--weights 以及 MCTS 相关参数，需要取值时从 args 中读取
+    pub fn parse_arg<'a>(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<(), String> {
+        use crate::headless::parse_value;
+
+        if arg == "--strategy" {
+            *self = match parse_value::<String>(arg, args.next())?.as_str() {
+                "random" => StrategyKind::Random,
+                "mcts" => StrategyKind::Mcts(MctsConfig::default()),
+                "ntuple" => StrategyKind::NTuple {
+                    weights: DEFAULT_WEIGHTS_FILE.to_string(),
+                },
+                other => return Err(format!("未知策略: {}", other)),
+            };
+            return Ok(());
+        }
+        if arg == "--weights" {
+            *self = StrategyKind::NTuple {
+                weights: parse_value(arg, args.next())?,
+            };
+            return Ok(());
+        }
+
+        let StrategyKind::Mcts(config) = self else {
+            return Err(format!("参数 {} 需要 --strategy mcts", arg));
+        };
+        match arg {
+            "--rollouts" => config.rollouts = parse_value(arg, args.next())?,
+            "--depth" => config.depth_limit = Some(parse_value(arg, args.next())?),
+            "--time-ms" => config.time_budget = Some(Duration::from_millis(parse_value(arg, args.next())?)),
+            "--threads" => config.threads = parse_value(arg, args.next())?,
+            "--guided" => config.guided = true,
+            _ => return Err(format!("未知参数: {}", arg)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+    use crate::cube::CubeBoard;
+
+    fn config(depth_limit: Option<usize>) -> MctsConfig {
+        MctsConfig {
+            rollouts: 20,
+            depth_limit,
+            time_budget: None,
+            threads: 2,
+            guided: false,
+        }
+    }
+
+    #[test]
+    fn test_illegal_directions_are_not_evaluated() {
+        let board = Board::from_cells([
+            [1024, 1024, 0, 0],
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+        ]);
+        let mut strategy = MctsStrategy::new(config(Some(0)), 1);
+        let values = strategy.evaluate(&board);
+
+        // 第一行已经贴在顶部，向上无法移动
+        assert_eq!(values[0], None);
+        assert_eq!(values[2], Some(2048.0));
+        assert!(matches!(strategy.choose(&board), Some(Direction::Left | Direction::Right)));
+    }
+
+    #[test]
+    fn test_random_strategy_only_picks_legal_moves() {
+        let board = Board::from_cells([
+            [2, 0, 0, 0],
+            [4, 0, 0, 0],
+            [8, 0, 0, 0],
+            [16, 0, 0, 0],
+        ]);
+        let mut strategy = RandomStrategy::new(3);
+        for _ in 0..20 {
+            assert_eq!(strategy.choose(&board), Some(Direction::Right));
+        }
+
+        let stuck = Board::from_cells([
+            [2, 4, 2, 4],
+            [4, 2, 4, 2],
+            [2, 4, 2, 4],
+            [4, 2, 4, 2],
+        ]);
+        assert_eq!(RandomStrategy::new(0).choose(&stuck), None);
+    }
+
+    #[test]
+    fn test_strategies_play_other_shapes() {
+        use crate::game::{Game, SeededGame};
+
+        let mut game = SeededGame::<CubeBoard>::new(5);
+        let kinds = [StrategyKind::Random, StrategyKind::Mcts(config(Some(2)))];
+        for kind in &kinds {
+            let mut strategy = kind.build::<CubeBoard>(1).unwrap();
+            let values = strategy.evaluate(game.board());
+            assert_eq!(values.len(), CubeBoard::MOVES.len());
+            let direction = strategy.choose(game.board()).unwrap();
+            assert!(game.apply(direction));
+        }
+
+        let ntuple = StrategyKind::NTuple {
+            weights: "weights.bin".to_string(),
+        };
+        assert!(ntuple.build::<CubeBoard>(1).is_err());
+    }
+
+    #[test]
+    fn test_heuristic_prefers_open_monotonic_boards() {
+        let ordered = Board::from_cells([
+            [128, 64, 32, 16],
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+            [0, 0, 0, 0],
+        ]);
+        let scattered = Board::from_cells([
+            [16, 0, 128, 0],
+            [0, 64, 0, 0],
+            [0, 0, 0, 32],
+            [0, 0, 0, 0],
+        ]);
+        assert!(heuristic(&ordered) > heuristic(&scattered));
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::ntuple::DEFAULT_WEIGHTS_FILE;
+use crate::strategy::{MctsConfig, StrategyKind};
+
+// 策略设置界面，游戏、立方体和多棋盘页面共用；返回设置是否有变化
+pub fn draw(ui: &mut egui::Ui, kind: &mut StrategyKind) -> bool {
+    let before = kind.clone();
+    ui.horizontal(|ui| {
+        ui.label("策略");
+        if ui.radio(matches!(kind, StrategyKind::Random), "随机").clicked() {
+            *kind = StrategyKind::Random;
+        }
+        if ui.radio(matches!(kind, StrategyKind::Mcts(_)), "蒙特卡洛").clicked()
+            && !matches!(kind, StrategyKind::Mcts(_))
+        {
+            *kind = StrategyKind::Mcts(MctsConfig::default());
+        }
+        if ui.radio(matches!(kind, StrategyKind::NTuple { .. }), "n-tuple").clicked()
+            && !matches!(kind, StrategyKind::NTuple { .. })
+        {
+            *kind = StrategyKind::NTuple {
+                weights: DEFAULT_WEIGHTS_FILE.to_string(),
+            };
+        }
+    });
+
+    if let StrategyKind::NTuple { weights } = kind {
+        ui.horizontal(|ui| {
+            ui.label("权重文件");
+            ui.text_edit_singleline(weights);
+        });
+    }
+
+    if let StrategyKind::Mcts(config) = kind {
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut config.rollouts).clamp_range(1..=10_000).prefix("模拟次数 "));
+            ui.add(egui::DragValue::new(&mut config.threads).clamp_range(1..=64).prefix("线程 "));
+            ui.checkbox(&mut config.guided, "引导模拟");
+        });
+        ui.horizontal(|ui| {
+            let mut millis = config.time_budget.map_or(0, |budget| budget.as_millis() as u64);
+            ui.add(egui::DragValue::new(&mut millis).clamp_range(0..=10_000).prefix("时间上限 ").suffix("毫秒"));
+            config.time_budget = (millis > 0).then(|| Duration::from_millis(millis));
+
+            let mut depth = config.depth_limit.unwrap_or(0);
+            ui.add(egui::DragValue::new(&mut depth).clamp_range(0..=10_000).prefix("深度上限 "));
+            config.depth_limit = (depth > 0).then_some(depth);
+        });
+    }
+    *kind != before
+}
@@ -0,0 +1,353 @@
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+use crate::headless::parse_value;
+use crate::rules::{GameRules, GameStatus};
+use crate::session::GameSession;
+use crate::spawner::{SpawnPolicy, SpawnRules};
+use crate::stats::{mean_std, paired_test, quantile, PairedTest};
+use crate::strategy::StrategyKind;
+
+// 策略对比：rust_2048_game tournament --strategy A [参数] --strategy B [参数] ... [选项]
+// 每个 --strategy 开始一个新的参赛策略，之后的策略参数只作用于它
+pub struct TournamentOptions {
+    pub games: u32,
+    pub seed: u64,
+    pub max_moves: Option<usize>,
+    pub entrants: Vec<StrategyKind>,
+    pub spawn: SpawnPolicy,
+    pub spawn_rules: SpawnRules,
+    pub rules: GameRules,
+    // 扩展名为 .csv 时输出CSV，否则输出Markdown
+    pub out: Option<String>,
+}
+
+impl TournamentOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = TournamentOptions {
+            games: 100,
+            seed: rand::random(),
+            max_moves: None,
+            entrants: Vec::new(),
+            spawn: SpawnPolicy::Random,
+            spawn_rules: SpawnRules::CLASSIC,
+            rules: GameRules::default(),
+            out: None,
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--games" => options.games = parse_value(arg, iter.next())?,
+                "--seed" => options.seed = parse_value(arg, iter.next())?,
+                "--max-moves" => options.max_moves = Some(parse_value(arg, iter.next())?),
+                "--out" => options.out = Some(parse_value(arg, iter.next())?),
+                "--spawn" => options.spawn = parse_value(arg, iter.next())?,
+                arg if SpawnRules::ARGS.contains(&arg) => options.spawn_rules.parse_arg(arg, &mut iter)?,
+                arg if GameRules::ARGS.contains(&arg) => options.rules.parse_arg(arg, &mut iter)?,
+                arg if StrategyKind::ARGS.contains(&arg) => {
+                    if arg == "--strategy" || options.entrants.is_empty() {
+                        options.entrants.push(StrategyKind::Random);
+                    }
+                    options.entrants.last_mut().unwrap().parse_arg(arg, &mut iter)?;
+                }
+                _ => return Err(format!("未知参数: {}", arg)),
+            }
+        }
+        if options.entrants.is_empty() {
+            return Err("至少需要用 --strategy 指定一个策略".to_string());
+        }
+        // 没有指定出块权重时使用变体的默认权重
+        if !args.iter().any(|arg| arg == "--spawn-weights") {
+            options.spawn_rules.weights = options.rules.merge.default_spawn_weights();
+        }
+        Ok(options)
+    }
+
+    // 统计达成率的方块：获胜目标以及之后的两个方块
+    fn win_tiles(&self) -> Vec<u32> {
+        let merge = self.rules.merge;
+        std::iter::successors(Some(self.rules.target), |&tile| merge.next_tile(tile))
+            .take(3)
+            .collect()
+    }
+}
+
+// 一个策略在所有对局上的结果，下标与种子一一对应
+struct Entrant {
+    label: String,
+    scores: Vec<f64>,
+    max_tiles: Vec<u32>,
+    moves: usize,
+    thinking: Duration,
+}
+
+impl Entrant {
+    fn win_rate(&self, tile: u32) -> f64 {
+        let wins = self.max_tiles.iter().filter(|&&max_tile| max_tile >= tile).count();
+        wins as f64 * 100.0 / self.max_tiles.len().max(1) as f64
+    }
+
+    fn millis_per_move(&self) -> f64 {
+        self.thinking.as_secs_f64() * 1000.0 / self.moves.max(1) as f64
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let options = TournamentOptions::parse(args)?;
+    let labels = labels(&options.entrants);
+
+    let mut entrants = Vec::new();
+    for (kind, label) in options.entrants.iter().zip(labels) {
+        let mut entrant = Entrant {
+            label,
+            scores: Vec::new(),
+            max_tiles: Vec::new(),
+            moves: 0,
+            thinking: Duration::ZERO,
+        };
+        // 所有策略使用同一组种子，开局和新方块的随机序列相同，差异只来自策略本身
+        for index in 0..options.games {
+            let seed = options.seed.wrapping_add(index as u64);
+            let mut session = GameSession::with_rules(seed, options.rules, options.spawn_rules).with_spawn_policy(options.spawn);
+            let mut strategy = kind.build(seed)?;
+            while !session.is_game_over() && options.max_moves.is_none_or(|max| session.moves() < max) {
+                if session.status() == GameStatus::Won {
+                    session.continue_playing();
+                }
+                let started = Instant::now();
+                let choice = strategy.choose(session.board());
+                let thinking = started.elapsed();
+                entrant.thinking += thinking;
+                // 限时模式只计算策略的思考时间
+                session.tick(thinking.as_secs_f64());
+                let Some(direction) = choice else {
+                    break;
+                };
+                session.apply(direction);
+            }
+            entrant.scores.push(session.score() as f64);
+            entrant.max_tiles.push(session.board().max_tile());
+            entrant.moves += session.moves();
+        }
+        eprintln!("{} 完成 {} 局", entrant.label, options.games);
+        entrants.push(entrant);
+    }
+
+    let report = match &options.out {
+        Some(path) if path.ends_with(".csv") => csv_report(&options, &entrants),
+        _ => markdown_report(&options, &entrants),
+    };
+    match &options.out {
+        Some(path) => {
+            std::fs::write(path, &report)?;
+            println!("报告已保存到 {}", path);
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+// 同一种策略出现多次时用参数区分
+fn labels(entrants: &[StrategyKind]) -> Vec<String> {
+    entrants
+        .iter()
+        .map(|kind| match kind {
+            StrategyKind::Random => "random".to_string(),
+            StrategyKind::Mcts(config) => format!(
+                "mcts(r={}{}{}{})",
+                config.rollouts,
+                config.depth_limit.map_or(String::new(), |depth| format!(",d={}", depth)),
+                config.time_budget.map_or(String::new(), |budget| format!(",t={}ms", budget.as_millis())),
+                if config.guided { ",guided" } else { "" }
+            ),
+            StrategyKind::NTuple { weights } => format!("ntuple({})", weights),
+        })
+        .enumerate()
+        .map(|(index, label)| {
+            let count = entrants.iter().filter(|&kind| *kind == entrants[index]).count();
+            if count > 1 { format!("{}#{}", label, index + 1) } else { label }
+        })
+        .collect()
+}
+
+fn summary(entrant: &Entrant) -> (f64, f64, [f64; 3]) {
+    let (mean, std) = mean_std(&entrant.scores).unwrap_or((0.0, 0.0));
+    let mut sorted = entrant.scores.clone();
+    sorted.sort_by(f64::total_cmp);
+    let quantiles = [0.1, 0.5, 0.9].map(|q| quantile(&sorted, q).unwrap_or(0.0));
+    (mean, std, quantiles)
+}
+
+// 每两个策略之间的配对检验
+fn pairs(entrants: &[Entrant]) -> Vec<(&Entrant, &Entrant, PairedTest)> {
+    let mut pairs = Vec::new();
+    for (i, a) in entrants.iter().enumerate() {
+        for b in &entrants[i + 1..] {
+            if let Some(test) = paired_test(&a.scores, &b.scores) {
+                pairs.push((a, b, test));
+            }
+        }
+    }
+    pairs
+}
+
+fn markdown_report(options: &TournamentOptions, entrants: &[Entrant]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "# 策略对比\n");
+    let _ = writeln!(
+        report,
+        "{} 局，种子 {} 到 {}，变体 {}，出块方式 {}，所有策略使用相同的种子。\n",
+        options.games,
+        options.seed,
+        options.seed.wrapping_add(options.games.saturating_sub(1) as u64),
+        options.rules.merge.label(),
+        options.spawn.label()
+    );
+
+    let win_tiles = options.win_tiles();
+    let _ = write!(report, "| 策略 | 平均分 | 标准差 | P10 | 中位数 | P90 | 最高分 |");
+    for tile in &win_tiles {
+        let _ = write!(report, " {} |", tile);
+    }
+    let _ = writeln!(report, " 每步毫秒 |");
+    let _ = writeln!(report, "|---|---:|---:|---:|---:|---:|---:|{}---:|", "---:|".repeat(win_tiles.len()));
+    for entrant in entrants {
+        let (mean, std, [p10, median, p90]) = summary(entrant);
+        let best = entrant.scores.iter().copied().fold(0.0, f64::max);
+        let _ = write!(
+            report,
+            "| {} | {:.1} | {:.1} | {:.0} | {:.0} | {:.0} | {:.0} |",
+            entrant.label, mean, std, p10, median, p90, best
+        );
+        for &tile in &win_tiles {
+            let _ = write!(report, " {:.1}% |", entrant.win_rate(tile));
+        }
+        let _ = writeln!(report, " {:.3} |", entrant.millis_per_move());
+    }
+
+    if entrants.len() > 1 {
+        let _ = writeln!(report, "\n## 配对比较\n");
+        let _ = writeln!(report, "| A | B | 平均分差 (A-B) | 95%置信区间 | p值 | 显著 |");
+        let _ = writeln!(report, "|---|---|---:|---|---:|---|");
+        for (a, b, test) in pairs(entrants) {
+            let margin = 1.96 * test.std_error;
+            let _ = writeln!(
+                report,
+                "| {} | {} | {:.1} | [{:.1}, {:.1}] | {:.4} | {} |",
+                a.label,
+                b.label,
+                test.difference,
+                test.difference - margin,
+                test.difference + margin,
+                test.p_value,
+                if test.p_value < 0.05 { "是" } else { "否" }
+            );
+        }
+    }
+    report
+}
+
+fn csv_label(label: &str) -> String {
+    format!("\"{}\"", label.replace('"', "\"\""))
+}
+
+// 第一张表为各策略的统计，空一行后是配对比较
+fn csv_report(options: &TournamentOptions, entrants: &[Entrant]) -> String {
+    let win_tiles = options.win_tiles();
+    let mut report = String::from("strategy,games,mean,std,p10,median,p90,max");
+    for tile in &win_tiles {
+        let _ = write!(report, ",win_{}", tile);
+    }
+    report.push_str(",ms_per_move\n");
+    for entrant in entrants {
+        let (mean, std, [p10, median, p90]) = summary(entrant);
+        let best = entrant.scores.iter().copied().fold(0.0, f64::max);
+        let _ = write!(
+            report,
+            "{},{},{:.1},{:.1},{:.0},{:.0},{:.0},{:.0}",
+            csv_label(&entrant.label),
+            entrant.scores.len(),
+            mean,
+            std,
+            p10,
+            median,
+            p90,
+            best
+        );
+        for &tile in &win_tiles {
+            let _ = write!(report, ",{:.4}", entrant.win_rate(tile) / 100.0);
+        }
+        let _ = writeln!(report, ",{:.3}", entrant.millis_per_move());
+    }
+
+    if entrants.len() > 1 {
+        report.push_str("\nstrategy_a,strategy_b,difference,ci_low,ci_high,p_value,significant\n");
+        for (a, b, test) in pairs(entrants) {
+            let margin = 1.96 * test.std_error;
+            let _ = writeln!(
+                report,
+                "{},{},{:.1},{:.1},{:.1},{:.4},{}",
+                csv_label(&a.label),
+                csv_label(&b.label),
+                test.difference,
+                test.difference - margin,
+                test.difference + margin,
+                test.p_value,
+                test.p_value < 0.05
+            );
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entrants() {
+        let args: Vec<String> = "--games 5 --strategy random --strategy mcts --rollouts 10 --strategy mcts"
+            .split(' ')
+            .map(String::from)
+            .collect();
+        let options = TournamentOptions::parse(&args).unwrap();
+        assert_eq!(options.games, 5);
+        assert_eq!(options.entrants.len(), 3);
+        assert!(matches!(&options.entrants[1], StrategyKind::Mcts(config) if config.rollouts == 10));
+        assert!(matches!(&options.entrants[2], StrategyKind::Mcts(config) if config.rollouts == 100));
+
+        assert!(TournamentOptions::parse(&["--games".to_string(), "5".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_weights_configure_previous_entrant() {
+        let args: Vec<String> = "--strategy random --strategy ntuple --weights f".split(' ').map(String::from).collect();
+        let options = TournamentOptions::parse(&args).unwrap();
+        assert_eq!(options.entrants.len(), 2);
+        assert_eq!(options.entrants[0], StrategyKind::Random);
+        assert_eq!(options.entrants[1], StrategyKind::NTuple { weights: "f".to_string() });
+    }
+
+    #[test]
+    fn test_csv_report_includes_pairs() {
+        let options = TournamentOptions::parse(&["--strategy".to_string(), "random".to_string()]).unwrap();
+        let entrant = |label: &str, scores: Vec<f64>| Entrant {
+            label: label.to_string(),
+            max_tiles: vec![0; scores.len()],
+            scores,
+            moves: 1,
+            thinking: Duration::ZERO,
+        };
+        let entrants = [entrant("a", vec![10.0, 20.0, 30.0]), entrant("b", vec![5.0, 12.0, 24.0])];
+        let report = csv_report(&options, &entrants);
+        assert!(report.contains("\nstrategy_a,strategy_b,difference,ci_low,ci_high,p_value,significant\n"));
+        assert!(report.contains("\"a\",\"b\",6.3,"));
+    }
+
+    #[test]
+    fn test_duplicate_labels_are_numbered() {
+        let labels = labels(&[StrategyKind::Random, StrategyKind::Random]);
+        assert_eq!(labels, vec!["random#1", "random#2"]);
+    }
+}
@@ -0,0 +1,105 @@
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::headless::parse_value;
+use crate::ntuple::{self, NTupleNetwork, DEFAULT_WEIGHTS_FILE, LARGE_PATTERNS, SMALL_PATTERNS};
+
+// n-tuple 网络训练：rust_2048_game train [选项]
+pub struct TrainOptions {
+    pub games: u64,
+    pub alpha: f32,
+    pub seed: u64,
+    pub out: String,
+    pub checkpoint_every: u64,
+    pub resume: Option<String>,
+    pub large: bool,
+    pub coherence: bool,
+}
+
+impl TrainOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = TrainOptions {
+            games: 100_000,
+            alpha: 0.1,
+            seed: rand::random(),
+            out: DEFAULT_WEIGHTS_FILE.to_string(),
+            checkpoint_every: 1000,
+            resume: None,
+            large: false,
+            coherence: false,
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--games" => options.games = parse_value(arg, iter.next())?,
+                "--alpha" => options.alpha = parse_value(arg, iter.next())?,
+                "--seed" => options.seed = parse_value(arg, iter.next())?,
+                "--out" => options.out = parse_value(arg, iter.next())?,
+                "--checkpoint-every" => options.checkpoint_every = parse_value(arg, iter.next())?,
+                "--resume" => options.resume = Some(parse_value(arg, iter.next())?),
+                "--large" => options.large = true,
+                "--tc" => options.coherence = true,
+                _ => return Err(format!("未知参数: {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let options = TrainOptions::parse(args)?;
+    let mut network = match &options.resume {
+        Some(path) => {
+            let network = NTupleNetwork::load(path)?;
+            if options.large && !network.has_patterns(&LARGE_PATTERNS) {
+                return Err(format!("{} 不是大网络的权重文件，不能和 --large 一起使用", path).into());
+            }
+            network
+        }
+        None if options.large => NTupleNetwork::new(&LARGE_PATTERNS),
+        None => NTupleNetwork::new(&SMALL_PATTERNS),
+    };
+    if options.coherence {
+        network.enable_coherence();
+        // 继续训练时接着使用之前的 TC 累计量，学习率不从头开始
+        if let Some(path) = &options.resume {
+            let tc_path = NTupleNetwork::coherence_path(path);
+            if std::path::Path::new(&tc_path).exists() {
+                network.load_coherence(&tc_path)?;
+            } else {
+                println!("没有找到 {}，TC 累计量从头开始", tc_path);
+            }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let started = Instant::now();
+    let (mut total_score, mut wins, mut best_tile) = (0u64, 0u64, 0);
+
+    for game in 1..=options.games {
+        let (score, max_tile) = ntuple::train_episode(&mut network, options.alpha, &mut rng);
+        total_score += score as u64;
+        wins += u64::from(max_tile >= 2048);
+        best_tile = best_tile.max(max_tile);
+
+        if game % options.checkpoint_every.max(1) == 0 || game == options.games {
+            let interval = (game - 1) % options.checkpoint_every.max(1) + 1;
+            println!(
+                "第{}局  平均分数 {:.1}  2048达成率 {:.1}%  最大方块 {}  用时 {:.0}秒",
+                game,
+                total_score as f64 / interval as f64,
+                wins as f64 * 100.0 / interval as f64,
+                best_tile,
+                started.elapsed().as_secs_f64()
+            );
+            network.save(&options.out)?;
+            network.save_coherence(&NTupleNetwork::coherence_path(&options.out))?;
+            (total_score, wins, best_tile) = (0, 0, 0);
+        }
+    }
+    println!("权重已保存到 {}", options.out);
+    Ok(())
+}
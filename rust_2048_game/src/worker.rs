@@ -0,0 +1,158 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::Board;
+use crate::game::Game;
+use crate::strategy::Strategy;
+
+enum Request<G: Game> {
+    Strategy(Box<dyn Strategy<G>>),
+    Cancel(Arc<AtomicBool>),
+    Choose(u64, G),
+}
+
+// 自动模式的后台线程：策略在线程中选择下一步，界面线程每帧最多等待给定的时间，
+// 耗时的策略不会卡住界面，快速的策略在一帧内仍然可以走很多步；方形和立方体页面共用
+pub struct MoveWorker<G: Game = Board> {
+    requests: Sender<Request<G>>,
+    results: Receiver<(u64, Option<G::Move>)>,
+    cancel: Arc<AtomicBool>,
+    // 已发出但尚未取回结果的请求
+    pending: Option<(u64, G)>,
+    next_id: u64,
+    name: String,
+}
+
+impl<G: Game> MoveWorker<G> {
+    pub fn new(strategy: Box<dyn Strategy<G>>) -> Self {
+        let (requests, receiver) = mpsc::channel::<Request<G>>();
+        let (sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            let mut strategy: Option<Box<dyn Strategy<G>>> = None;
+            for request in receiver {
+                match request {
+                    Request::Strategy(next) => strategy = Some(next),
+                    Request::Cancel(cancel) => {
+                        if let Some(strategy) = strategy.as_mut() {
+                            strategy.set_cancel_flag(cancel);
+                        }
+                    }
+                    Request::Choose(id, board) => {
+                        let direction = strategy.as_mut().and_then(|strategy| strategy.choose(&board));
+                        // 界面已经退出时结束线程
+                        if sender.send((id, direction)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut worker = Self {
+            requests,
+            results,
+            cancel: Arc::new(AtomicBool::new(false)),
+            pending: None,
+            next_id: 0,
+            name: String::new(),
+        };
+        worker.set_strategy(strategy);
+        worker
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // 换用新的策略，正在进行的计算被取消
+    pub fn set_strategy(&mut self, mut strategy: Box<dyn Strategy<G>>) {
+        self.cancel();
+        self.name = strategy.name().to_string();
+        strategy.set_cancel_flag(self.cancel.clone());
+        let _ = self.requests.send(Request::Strategy(strategy));
+    }
+
+    // 放弃未取回的请求，并让策略尽快结束正在进行的计算
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        let _ = self.requests.send(Request::Cancel(self.cancel.clone()));
+        self.pending = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    // 返回这个棋盘的下一步，最多等待 wait；还没算完时返回 None，下一帧用同一个棋盘继续等待
+    pub fn next_move(&mut self, board: &G, wait: Duration) -> Option<Option<G::Move>> {
+        // 棋盘已经变化（新的一局或读取存档），旧的请求作废
+        if self.pending.as_ref().is_some_and(|(_, pending)| pending != board) {
+            self.cancel();
+        }
+        let id = match &self.pending {
+            Some((id, _)) => *id,
+            None => {
+                self.next_id += 1;
+                let _ = self.requests.send(Request::Choose(self.next_id, board.clone()));
+                self.pending = Some((self.next_id, board.clone()));
+                self.next_id
+            }
+        };
+
+        let deadline = Instant::now() + wait;
+        loop {
+            match self.results.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok((result, direction)) if result == id => {
+                    self.pending = None;
+                    return Some(direction);
+                }
+                // 已作废的请求的结果
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+    use crate::strategy::{MctsConfig, MctsStrategy, RandomStrategy};
+
+    fn wait(worker: &mut MoveWorker, board: &Board) -> Option<Direction> {
+        for _ in 0..1000 {
+            if let Some(direction) = worker.next_move(board, Duration::from_millis(5)) {
+                return direction;
+            }
+        }
+        panic!("计算超时");
+    }
+
+    #[test]
+    fn test_moves_follow_the_current_board() {
+        let only_right = Board::from_cells([[2, 0, 0, 0], [4, 0, 0, 0], [8, 0, 0, 0], [16, 0, 0, 0]]);
+        let only_left = Board::from_cells([[0, 0, 0, 2], [0, 0, 0, 4], [0, 0, 0, 8], [0, 0, 0, 16]]);
+
+        // 很慢的策略，第一个请求在等待时间内算不完
+        let config = MctsConfig {
+            rollouts: 1_000_000,
+            time_budget: Some(Duration::from_secs(10)),
+            ..MctsConfig::default()
+        };
+        let mut worker = MoveWorker::new(Box::new(MctsStrategy::new(config, 1)));
+        assert_eq!(worker.next_move(&only_right, Duration::ZERO), None);
+        assert!(worker.is_pending());
+
+        // 换成快速的策略后，旧的计算被取消，新棋盘得到自己的结果
+        worker.set_strategy(Box::new(RandomStrategy::new(1)));
+        assert_eq!(wait(&mut worker, &only_left), Some(Direction::Left));
+        assert_eq!(wait(&mut worker, &only_right), Some(Direction::Right));
+        assert!(!worker.is_pending());
+        assert_eq!(worker.name(), "random");
+    }
+}